@@ -1,4 +1,4 @@
-use cosmwasm_std::{StdError, Uint128};
+use cosmwasm_std::{Decimal, StdError, Uint128};
 use cw_utils::PaymentError;
 use cw_controllers::HookError;
 use thiserror::Error;
@@ -16,6 +16,9 @@ pub enum ContractError {
     #[error("UnauthorizedOperator")]
     UnauthorizedOperator {},
 
+    #[error("UnauthorizedCreator")]
+    UnauthorizedCreator {},
+
     #[error("InvalidPrice")]
     InvalidPrice {},
 
@@ -34,6 +37,9 @@ pub enum ContractError {
     #[error("AskNotFound")]
     AskNotFound {},
 
+    #[error("AskNotExpired")]
+    AskNotExpired {},
+
     #[error("BidExpired")]
     BidExpired {},
 
@@ -43,22 +49,49 @@ pub enum ContractError {
     #[error("InvalidFinder: {0}")]
     InvalidFinder(String),
 
+    #[error("InvalidDenom: {0}")]
+    InvalidDenom(String),
+
     #[error("PriceTooSmall: {0}")]
     PriceTooSmall(Uint128),
 
+    #[error("BidIncrementTooSmall: min next bid is {0}")]
+    BidIncrementTooSmall(Uint128),
+
     #[error("InvalidListingFee: {0}")]
     InvalidListingFee(Uint128),
 
+    #[error("InvalidListingFeeShares")]
+    InvalidListingFeeShares {},
+
+    #[error("InsufficientListingFees: {0}")]
+    InsufficientListingFees(Uint128),
+
     #[error("Token reserved")]
     TokenReserved {},
 
     #[error("Invalid finders fee bps: {0}")]
     InvalidFindersFeeBps(u64),
 
-    #[error("Invalid finders fee bps: {0}")]
+    #[error("Invalid trading fee bps: {0}")]
     InvalidTradingFeeBps(u64),
 
-    #[error("Invalid finders fee bps: {0}")]
+    #[error("Invalid max royalty bps: {0}")]
+    InvalidMaxRoyaltyBps(u64),
+
+    #[error("InvalidRoyaltyRate: {0}")]
+    InvalidRoyaltyRate(Decimal),
+
+    #[error("Royalty shares must have a non-zero weight")]
+    InvalidRoyaltyShares {},
+
+    #[error("Invalid auction cancel penalty bps: {0}")]
+    InvalidCancelPenaltyBps(u64),
+
+    #[error("InvalidCancelPenalty: {0}")]
+    InvalidCancelPenalty(Uint128),
+
+    #[error("Invalid bid removal reward bps: {0}")]
     InvalidBidRemovalRewardBps(u64),
 
     #[error("{0}")]
@@ -91,9 +124,57 @@ pub enum ContractError {
     #[error("Auction not ended")]
     AuctionNotEnded {},
 
+    #[error("Auction has no bids")]
+    AuctionHasNoBids {},
+
+    #[error("Reserve price not met")]
+    ReserveNotMet {},
+
     #[error("ids mismatch")]
     IdMismatch {},
 
+    #[error("CollectionMismatch")]
+    CollectionMismatch {},
+
+    #[error("CollectionNotAllowed: {0}")]
+    CollectionNotAllowed(String),
+
+    #[error("CollectionNotFound: {0}")]
+    CollectionNotFound(String),
+
+    #[error("SaleTypeNotAllowed")]
+    SaleTypeNotAllowed {},
+
     #[error("auction cannot remove")]
-    AuctionNotRemove {}
+    AuctionNotRemove {},
+
+    #[error("max bidder cannot remove bid")]
+    MaxBidderNotRemove {},
+
+    #[error("Operation not supported for this sale type")]
+    InvalidSaleType {},
+
+    #[error("Buy now price must be above the starting and reserve prices")]
+    InvalidBuyNowPrice {},
+
+    #[error("Dutch auction end price must be below the starting price")]
+    InvalidDutchAuction {},
+
+    #[error("Sealed auction reveal duration must be within the ask duration")]
+    InvalidSealedAuction {},
+
+    #[error("Operation not supported in this phase of the sealed auction")]
+    InvalidAuctionPhase {},
+
+    #[error("Revealed bid does not match its commitment")]
+    InvalidCommitment {},
+
+    #[error("Revealed bid exceeds its deposit")]
+    RevealExceedsDeposit {},
+
+    #[error("Bid already revealed")]
+    AlreadyRevealed {},
+
+    #[error("Too many sealed bids")]
+    TooManySealedBids {},
 }
@@ -1,34 +1,51 @@
 use crate::error::ContractError;
 use crate::helpers::{map_validate, ExpiryRange};
 use crate::msg::{
-    AskHookMsg, BidHookMsg, ExecuteMsg, HookAction, InstantiateMsg,
-    SaleHookMsg,
+    AskHookMsg, AskInfo, BidHookMsg, CollectionBidHookMsg, ExecuteMsg, HookAction, InstantiateMsg,
+    MigrateMsg, ReceiveCw20Msg, ReceiveNftMsg, SaleHookMsg,
 };
 use crate::state::{
-    ask_key, asks, bid_key, bids, Ask, Bid, Order, SaleType, SudoParams, TokenId, ASK_HOOKS, BID_HOOKS, SALE_HOOKS,
-    SUDO_PARAMS
+    ask_key, asks, bid_key, bids, collection_bid_key, collection_bids, denom_key, Ask, AskKey, Bid,
+    BidIncrement, BidKey, CollectionBid, CollectionListMode, Order, RegisteredCollection,
+    RoyaltyInfo, SaleType, SealedAuction, SealedAuctionPhase, SealedAuctionPricing, SealedBid,
+    SudoParams, TokenId, ALLOWED_COLLECTIONS, ASK_HOOKS, BID_HOOKS, COLLECTIONS,
+    COLLECTION_BID_HOOKS, COLLECTION_PARAMS, CW20_TOKENS, DENIED_COLLECTIONS, DENOMS,
+    HIDDEN_RESERVES, LISTING_FEES, PROXY_CAPS, ROYALTIES, SALE_HOOKS, SEALED_BIDS, SUDO_PARAMS,
 };
 use cw721_base::Metadata;
 
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    coin, to_binary, Addr, BankMsg, Coin, Decimal, Deps, DepsMut, Empty, Env, Event, MessageInfo,
-    Reply, StdError, StdResult, Storage, Uint128, WasmMsg, Response, SubMsg, from_binary
+    coin, from_binary, to_binary, Addr, BankMsg, Binary, ContractInfoResponse, CosmosMsg, Decimal,
+    Deps, DepsMut, Env, Event, MessageInfo, Order as StorageOrder, Reply, Response, StdError,
+    StdResult, Storage, SubMsg, Timestamp, Uint128, WasmMsg, WasmQuery,
 };
 use cw2::set_contract_version;
+use cw20::{Balance, Cw20CoinVerified, Cw20ExecuteMsg, Cw20ReceiveMsg, Denom};
 use cw721_base::ExecuteMsg as Cw721ExecuteMsg;
 use cw721_base::QueryMsg as Cw721QueryMsg;
 use cw721_base::CollectionInfoResponse;
+use cw721_base::msg::{Royalty, RoyaltyShare};
 use cw721::{Cw721ReceiveMsg, NftInfoResponse};
-use cw_storage_plus::Item;
-use cw_utils::{may_pay, must_pay, nonpayable, Duration};
+use cw_storage_plus::{Item, Map, PrefixBound};
+use cw_utils::{may_pay, maybe_addr, nonpayable, one_coin};
 use schemars::JsonSchema;
 use semver::Version;
 use serde::{Deserialize, Serialize};
-// use sg1::fair_burn;
+use sha2::{Digest, Sha256};
 
+/// Payment denom of the contract before accepted denoms were set by governance
 pub const NATIVE_DENOM: &str = "uheart";
+/// Basis points in 100%, the upper bound for fees
+pub const MAX_FEE_BPS: u64 = 10000;
+
+/// Max number of commitments on a sealed auction, which bounds the gas used to settle it
+pub const MAX_SEALED_BIDS: usize = 50;
+
+/// Default and max number of orders processed by `ProcessExpired`
+const DEFAULT_PROCESS_LIMIT: u32 = 10;
+const MAX_PROCESS_LIMIT: u32 = 30;
 
 // Version info for migration info
 const CONTRACT_NAME: &str = "crates.io:human-marketplace";
@@ -46,17 +63,45 @@ pub fn instantiate(
     msg.ask_expiry.validate()?;
     msg.bid_expiry.validate()?;
 
+    if msg.trading_fee_bps > MAX_FEE_BPS {
+        return Err(ContractError::InvalidTradingFeeBps(msg.trading_fee_bps));
+    }
+    if msg.max_finders_fee_bps > MAX_FEE_BPS {
+        return Err(ContractError::InvalidFindersFeeBps(msg.max_finders_fee_bps));
+    }
+    if msg.auction_cancel_penalty_bps > MAX_FEE_BPS {
+        return Err(ContractError::InvalidCancelPenaltyBps(
+            msg.auction_cancel_penalty_bps,
+        ));
+    }
+    if msg.max_royalty_bps > MAX_FEE_BPS {
+        return Err(ContractError::InvalidMaxRoyaltyBps(msg.max_royalty_bps));
+    }
+
     let params = SudoParams {
-        // trading_fee_percent: Decimal::percent(msg.trading_fee_bps),
+        trading_fee_percent: Decimal::percent(msg.trading_fee_bps),
+        fee_recipient: deps.api.addr_validate(&msg.fee_recipient)?,
         ask_expiry: msg.ask_expiry,
         bid_expiry: msg.bid_expiry,
         operators: map_validate(deps.api, &msg.operators)?,
-        // max_finders_fee_percent: Decimal::percent(msg.max_finders_fee_bps),
-        min_price: msg.min_price,
+        max_finders_fee_percent: Decimal::percent(msg.max_finders_fee_bps),
+        auction_cancel_penalty_percent: Decimal::percent(msg.auction_cancel_penalty_bps),
         listing_fee: msg.listing_fee,
+        refund_listing_fee_on_sale: msg.refund_listing_fee_on_sale,
+        keeper_reward: msg.keeper_reward,
+        extension_window: msg.extension_window,
+        extension_duration: msg.extension_duration,
+        max_extension: msg.max_extension,
+        min_bid_increment: msg.min_bid_increment,
+        collection_list_mode: msg.collection_list_mode,
+        max_royalty_rate: Decimal::from_ratio(msg.max_royalty_bps, MAX_FEE_BPS),
     };
     SUDO_PARAMS.save(deps.storage, &params)?;
 
+    for min_price in msg.denoms {
+        DENOMS.save(deps.storage, &min_price.denom, &min_price.amount)?;
+    }
+
     if let Some(hook) = msg.sale_hook {
         SALE_HOOKS.add_hook(deps.storage, deps.api.addr_validate(&hook)?)?;
     }
@@ -66,21 +111,27 @@ pub fn instantiate(
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 
-pub struct AskInfo {
-    sale_type: SaleType,
+pub struct BidInfo {
     collection: Addr,
     token_id: TokenId,
-    price: Coin,
-    funds_recipient: Option<Addr>,
-    expires: u64,
+    expires: Option<u64>,
+    finder: Option<Addr>,
+    /// Bid the min increment above competing bids, up to the escrowed price
+    proxy: bool,
 }
 
-
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CollectionBidInfo {
+    collection: Addr,
+    finders_fee_bps: Option<u64>,
+    expires: u64,
+}
 
-pub struct BidInfo {
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CommitBidInfo {
     collection: Addr,
     token_id: TokenId,
+    commitment: Binary,
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
@@ -93,42 +144,85 @@ pub fn execute(
     let api = deps.api;
 
     match msg {
-        ExecuteMsg::ReceiveNft(rcv_msg) => execute_set_ask(deps, env, info, rcv_msg),
-        
+        ExecuteMsg::ReceiveNft(rcv_msg) => execute_receive_nft(deps, env, info, rcv_msg),
+        ExecuteMsg::Receive(cw20_msg) => execute_receive_cw20(deps, env, info, cw20_msg),
         ExecuteMsg::RemoveAsk {
             collection,
             token_id,
-        } => execute_remove_ask(deps, info, api.addr_validate(&collection)?, token_id),
-        ExecuteMsg::SetBid {
-            collection,
-            token_id,
-        } => execute_set_bid(
+        } => execute_remove_ask(
             deps,
             env,
-            info,
-            BidInfo {
-                collection: api.addr_validate(&collection)?,
-                token_id,
-            },
+            info.sender,
+            api.addr_validate(&collection)?,
+            token_id,
+            Balance::from(info.funds),
         ),
-        // ExecuteMsg::RemoveBid {
-        //     collection,
-        //     token_id,
-        // } => execute_remove_bid(deps, env, info, api.addr_validate(&collection)?, token_id),
+        ExecuteMsg::SetBid {
+            collection,
+            token_id,
+            expires,
+            finder,
+        } => {
+            let payment = one_coin(&info)?;
+            execute_set_bid(
+                deps,
+                env,
+                info.sender,
+                BidInfo {
+                    collection: api.addr_validate(&collection)?,
+                    token_id,
+                    expires,
+                    finder: maybe_addr(api, finder)?,
+                    proxy: false,
+                },
+                payment.amount,
+                Denom::Native(payment.denom),
+            )
+        }
+        ExecuteMsg::SetProxyBid {
+            collection,
+            token_id,
+            finder,
+        } => {
+            let payment = one_coin(&info)?;
+            execute_set_bid(
+                deps,
+                env,
+                info.sender,
+                BidInfo {
+                    collection: api.addr_validate(&collection)?,
+                    token_id,
+                    expires: None,
+                    finder: maybe_addr(api, finder)?,
+                    proxy: true,
+                },
+                payment.amount,
+                Denom::Native(payment.denom),
+            )
+        }
+        ExecuteMsg::RemoveBid {
+            collection,
+            token_id,
+        } => execute_remove_bid(deps, env, info, api.addr_validate(&collection)?, token_id),
         ExecuteMsg::AcceptBid {
             collection,
             token_id,
+            bidder,
+            finder,
         } => execute_accept_bid(
             deps,
             env,
             info,
             api.addr_validate(&collection)?,
             token_id,
+            maybe_addr(api, bidder)?,
+            maybe_addr(api, finder)?,
         ),
         ExecuteMsg::UpdateAskPrice {
             collection,
             token_id,
             price,
+            denom,
         } => execute_update_ask_price(
             deps,
             env,
@@ -136,6 +230,229 @@ pub fn execute(
             api.addr_validate(&collection)?,
             token_id,
             price,
+            denom,
+        ),
+        ExecuteMsg::RemoveStaleAsks {
+            collection,
+            token_ids,
+        } => execute_remove_stale_asks(deps, env, info, api.addr_validate(&collection)?, token_ids),
+        ExecuteMsg::ProcessExpired { limit } => execute_process_expired(deps, env, info, limit),
+        ExecuteMsg::SetRoyalty {
+            collection,
+            recipient,
+            royalty_rate,
+            shares,
+        } => execute_set_royalty(
+            deps,
+            info,
+            api.addr_validate(&collection)?,
+            api.addr_validate(&recipient)?,
+            royalty_rate,
+            shares.unwrap_or_default(),
+        ),
+        ExecuteMsg::RemoveRoyalty { collection } => {
+            execute_remove_royalty(deps, info, api.addr_validate(&collection)?)
+        }
+        ExecuteMsg::SetCollectionBid {
+            collection,
+            finders_fee_bps,
+            expires,
+        } => {
+            let payment = one_coin(&info)?;
+            execute_set_collection_bid(
+                deps,
+                env,
+                info.sender,
+                CollectionBidInfo {
+                    collection: api.addr_validate(&collection)?,
+                    finders_fee_bps,
+                    expires,
+                },
+                payment.amount,
+                Denom::Native(payment.denom),
+            )
+        }
+        ExecuteMsg::RemoveCollectionBid { collection } => {
+            execute_remove_collection_bid(deps, env, info, api.addr_validate(&collection)?)
+        }
+        ExecuteMsg::CommitBid {
+            collection,
+            token_id,
+            commitment,
+        } => {
+            let payment = one_coin(&info)?;
+            execute_commit_bid(
+                deps,
+                env,
+                info.sender,
+                CommitBidInfo {
+                    collection: api.addr_validate(&collection)?,
+                    token_id,
+                    commitment,
+                },
+                payment.amount,
+                Denom::Native(payment.denom),
+            )
+        }
+        ExecuteMsg::RevealBid {
+            collection,
+            token_id,
+            price,
+            salt,
+        } => execute_reveal_bid(
+            deps,
+            env,
+            info,
+            api.addr_validate(&collection)?,
+            token_id,
+            price,
+            salt,
+        ),
+        ExecuteMsg::SettleAuction {
+            collection,
+            token_id,
+        } => execute_settle_auction(deps, env, info, api.addr_validate(&collection)?, token_id),
+        ExecuteMsg::AcceptCollectionBid {
+            collection,
+            token_id,
+            bidder,
+            finder,
+        } => execute_accept_collection_bid(
+            deps,
+            env,
+            info,
+            api.addr_validate(&collection)?,
+            token_id,
+            api.addr_validate(&bidder)?,
+            maybe_addr(api, finder)?,
+        ),
+    }
+}
+
+/// Handles an NFT sent to the marketplace with `SendNft`
+pub fn execute_receive_nft(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    rcv_msg: Cw721ReceiveMsg,
+) -> Result<Response, ContractError> {
+    match from_binary(&rcv_msg.msg)? {
+        ReceiveNftMsg::SetAsk(ask_info) => execute_set_ask(deps, env, info, rcv_msg, ask_info),
+        ReceiveNftMsg::AcceptBid {
+            bidder,
+            finder,
+            finders_fee_bps,
+        } => {
+            let bidder = deps.api.addr_validate(&bidder)?;
+            let finder = maybe_addr(deps.api, finder)?;
+            execute_receive_bid(deps, env, info, rcv_msg, bidder, finder, finders_fee_bps)
+        }
+        ReceiveNftMsg::AcceptCollectionBid { bidder, finder } => {
+            let bidder = deps.api.addr_validate(&bidder)?;
+            let finder = maybe_addr(deps.api, finder)?;
+            execute_receive_collection_bid(deps, env, info, rcv_msg, bidder, finder)
+        }
+    }
+}
+
+/// Handles CW20 tokens sent to the marketplace with `Send`
+pub fn execute_receive_cw20(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    cw20_msg: Cw20ReceiveMsg,
+) -> Result<Response, ContractError> {
+    nonpayable(&info)?;
+
+    // The sender of `Receive` is the token contract, which is checked against
+    // the accepted tokens when the price is validated
+    let denom = Denom::Cw20(info.sender.clone());
+    let api = deps.api;
+    let sender = api.addr_validate(&cw20_msg.sender)?;
+
+    match from_binary(&cw20_msg.msg)? {
+        ReceiveCw20Msg::SetBid {
+            collection,
+            token_id,
+            expires,
+            finder,
+        } => execute_set_bid(
+            deps,
+            env,
+            sender,
+            BidInfo {
+                collection: api.addr_validate(&collection)?,
+                token_id,
+                expires,
+                finder: maybe_addr(api, finder)?,
+                proxy: false,
+            },
+            cw20_msg.amount,
+            denom,
+        ),
+        ReceiveCw20Msg::SetProxyBid {
+            collection,
+            token_id,
+            finder,
+        } => execute_set_bid(
+            deps,
+            env,
+            sender,
+            BidInfo {
+                collection: api.addr_validate(&collection)?,
+                token_id,
+                expires: None,
+                finder: maybe_addr(api, finder)?,
+                proxy: true,
+            },
+            cw20_msg.amount,
+            denom,
+        ),
+        ReceiveCw20Msg::RemoveAsk {
+            collection,
+            token_id,
+        } => execute_remove_ask(
+            deps,
+            env,
+            sender,
+            api.addr_validate(&collection)?,
+            token_id,
+            Balance::Cw20(Cw20CoinVerified {
+                address: info.sender,
+                amount: cw20_msg.amount,
+            }),
+        ),
+        ReceiveCw20Msg::SetCollectionBid {
+            collection,
+            finders_fee_bps,
+            expires,
+        } => execute_set_collection_bid(
+            deps,
+            env,
+            sender,
+            CollectionBidInfo {
+                collection: api.addr_validate(&collection)?,
+                finders_fee_bps,
+                expires,
+            },
+            cw20_msg.amount,
+            denom,
+        ),
+        ReceiveCw20Msg::CommitBid {
+            collection,
+            token_id,
+            commitment,
+        } => execute_commit_bid(
+            deps,
+            env,
+            sender,
+            CommitBidInfo {
+                collection: api.addr_validate(&collection)?,
+                token_id,
+                commitment,
+            },
+            cw20_msg.amount,
+            denom,
         ),
     }
 }
@@ -146,34 +463,106 @@ pub fn execute_set_ask(
     env: Env,
     info: MessageInfo,
     rcv_msg: Cw721ReceiveMsg,
+    ask_info: AskInfo,
 ) -> Result<Response, ContractError> {
-    let ask_info: AskInfo = from_binary(&rcv_msg.msg)?;
-    
     let AskInfo {
         sale_type,
         collection,
         token_id,
         price,
+        denom,
         funds_recipient,
+        finders_fee_bps,
         expires,
+        min_bid_increment,
+        reserve_price,
+        hide_reserve_price,
+        buy_now_price,
+        allow_early_accept,
+        dutch_auction,
+        sealed_auction,
     } = ask_info;
 
     if rcv_msg.token_id != token_id {
         return Err(ContractError::IdMismatch{});
     }
+    // The sender of `ReceiveNft` is the collection that now escrows the NFT
+    if collection != info.sender {
+        return Err(ContractError::CollectionMismatch {});
+    }
+    collection_validate(deps.storage, &collection)?;
 
-    price_validate(deps.storage, &price)?;
+    let min_price = price_validate(deps.storage, &collection, price, &denom)?;
 
-    let params = SUDO_PARAMS.load(deps.storage)?;
+    if sale_type != SaleType::Auction
+        && (reserve_price.is_some() || buy_now_price.is_some() || allow_early_accept)
+    {
+        return Err(ContractError::InvalidSaleType {});
+    }
+    if let Some(buy_now_price) = buy_now_price {
+        if buy_now_price <= price || buy_now_price < reserve_price.unwrap_or_default() {
+            return Err(ContractError::InvalidBuyNowPrice {});
+        }
+    }
+    match (&sale_type, &dutch_auction) {
+        (SaleType::DutchAuction, Some(dutch_auction)) => {
+            price_validate(deps.storage, &collection, dutch_auction.end_price, &denom)?;
+            if dutch_auction.end_price >= price {
+                return Err(ContractError::InvalidDutchAuction {});
+            }
+        }
+        (SaleType::DutchAuction, None) => return Err(ContractError::InvalidDutchAuction {}),
+        (_, Some(_)) => return Err(ContractError::InvalidSaleType {}),
+        (_, None) => {}
+    }
+    let sealed_auction = match (&sale_type, sealed_auction) {
+        (SaleType::SealedAuction, Some(sealed_auction)) => {
+            if sealed_auction.reveal_duration == 0 || sealed_auction.reveal_duration >= expires {
+                return Err(ContractError::InvalidSealedAuction {});
+            }
+            Some(SealedAuction {
+                pricing: sealed_auction.pricing,
+                reveal_at: env
+                    .block
+                    .time
+                    .plus_seconds(expires - sealed_auction.reveal_duration),
+            })
+        }
+        (SaleType::SealedAuction, None) => return Err(ContractError::InvalidSealedAuction {}),
+        (_, Some(_)) => return Err(ContractError::InvalidSaleType {}),
+        (_, None) => None,
+    };
+
+    if let Some(sale_types) = COLLECTION_PARAMS
+        .may_load(deps.storage, &collection)?
+        .and_then(|overrides| overrides.sale_types)
+    {
+        if !sale_types.contains(&sale_type) {
+            return Err(ContractError::SaleTypeNotAllowed {});
+        }
+    }
+
+    let params = load_params(deps.storage, &collection)?;
     params.ask_expiry.is_valid(expires)?;
 
+    if let Some(fee) = finders_fee_bps {
+        if Decimal::percent(fee) > params.max_finders_fee_percent {
+            return Err(ContractError::InvalidFindersFeeBps(fee));
+        }
+    }
+
     // Check if msg has correct listing fee
-    let listing_fee = may_pay(&info, NATIVE_DENOM)?;
-    if listing_fee != params.listing_fee {
+    let listing_fee = may_pay(&info, &params.listing_fee.denom)?;
+    if listing_fee != params.listing_fee.amount {
         return Err(ContractError::InvalidListingFee(listing_fee));
     }
+    if !listing_fee.is_zero() {
+        LISTING_FEES.update(deps.storage, &params.listing_fee.denom, |collected| {
+            StdResult::Ok(collected.unwrap_or_default() + listing_fee)
+        })?;
+    }
 
-    let seller = info.sender;
+    let seller = deps.api.addr_validate(rcv_msg.sender.as_str())?;
     let now = env.block.time;
 
     let nft_info: NftInfoResponse<Metadata> = deps
@@ -186,15 +575,39 @@ pub fn execute_set_ask(
         collection: collection.clone(),
         token_id: token_id.clone(),
         img_url: nft_info.extension.image_url,
-        seller: deps.api.addr_validate(rcv_msg.sender.as_str())?,
-        price: price.amount,
+        seller: seller.clone(),
+        price,
+        denom: denom.clone(),
         funds_recipient,
+        finders_fee_bps,
         expires_at: now.plus_seconds(expires),
         max_bidder: Some(env.contract.address.clone()),
-        max_bid: Some(params.min_price),
+        max_bid: Some(min_price),
+        total_extension: 0,
+        min_bid_increment,
+        reserve_price: if hide_reserve_price {
+            None
+        } else {
+            reserve_price
+        },
+        buy_now_price,
+        allow_early_accept,
+        dutch_auction,
+        sealed_auction,
+        listing_fee: (!listing_fee.is_zero())
+            .then(|| coin(listing_fee.u128(), &params.listing_fee.denom)),
+        created_at: now,
     };
     store_ask(deps.storage, &ask)?;
 
+    if let (Some(reserve_price), true) = (reserve_price, hide_reserve_price) {
+        HIDDEN_RESERVES.save(
+            deps.storage,
+            ask_key(&collection, &token_id),
+            &reserve_price,
+        )?;
+    }
+
     let hook = prepare_ask_hook(deps.as_ref(), &ask, HookAction::Create)?;
 
     let event = Event::new("set-ask")
@@ -202,6 +615,7 @@ pub fn execute_set_ask(
         .add_attribute("token_id", token_id.to_string())
         .add_attribute("seller", seller)
         .add_attribute("price", price.to_string())
+        .add_attribute("denom", denom_key(&denom))
         .add_attribute("expires", expires.to_string())
         .add_attribute("time", env.block.time.to_string());
 
@@ -215,23 +629,72 @@ pub fn execute_set_ask(
 /// Removes the ask on a particular NFT
 pub fn execute_remove_ask(
     deps: DepsMut,
-    info: MessageInfo,
+    env: Env,
+    seller: Addr,
     collection: Addr,
     token_id: TokenId,
+    payment: Balance,
 ) -> Result<Response, ContractError> {
-    nonpayable(&info)?;
-
     let key = ask_key(&collection, &token_id);
     let ask = asks().load(deps.storage, key.clone())?;
 
-    let owner = ask.clone().seller;
-    only_owner_nft(&info, owner)?;
+    if ask.seller != seller {
+        return Err(ContractError::UnauthorizedOwner {});
+    }
 
-    if ask.sale_type == SaleType::Auction {
+    if ask.sale_type == SaleType::SealedAuction {
         return Err(ContractError::AuctionNotRemove {});
     }
 
-    asks().remove(deps.storage, key)?;
+    // An auction with bids can only be cancelled if governance sets a penalty for it,
+    // which is paid by the seller to the highest bidder
+    let has_bids = ask.sale_type == SaleType::Auction
+        && ask.max_bidder.as_ref() != Some(&env.contract.address);
+    let max_bid = ask.max_bid.unwrap_or_default();
+    let penalty = if has_bids {
+        let params = SUDO_PARAMS.load(deps.storage)?;
+        if params.auction_cancel_penalty_percent.is_zero() {
+            return Err(ContractError::AuctionNotRemove {});
+        }
+        max_bid * params.auction_cancel_penalty_percent / Uint128::from(100u128)
+    } else {
+        Uint128::zero()
+    };
+
+    let paid = match &payment {
+        payment if payment.is_empty() => Uint128::zero(),
+        Balance::Native(balance)
+            if balance.0.len() == 1 && ask.denom == Denom::Native(balance.0[0].denom.clone()) =>
+        {
+            balance.0[0].amount
+        }
+        Balance::Cw20(coin) if ask.denom == Denom::Cw20(coin.address.clone()) => coin.amount,
+        _ => return Err(ContractError::InvalidDenom(payment.to_string())),
+    };
+    if paid != penalty {
+        return Err(ContractError::InvalidCancelPenalty(paid));
+    }
+
+    remove_ask(deps.storage, key.clone())?;
+    HIDDEN_RESERVES.remove(deps.storage, key.clone());
+
+    let mut res = Response::new();
+
+    if has_bids {
+        // The highest bid is refunded along with the penalty
+        let max_bidder = ask.max_bidder.clone().unwrap();
+        let max_price = PROXY_CAPS
+            .may_load(deps.storage, key.clone())?
+            .unwrap_or(max_bid);
+        PROXY_CAPS.remove(deps.storage, key);
+        res = res.add_message(transfer_msg(&ask.denom, max_price + penalty, &max_bidder)?);
+
+        let max_bid_key = bid_key(&collection, &token_id, &max_bidder);
+        if let Some(bid) = bids().may_load(deps.storage, max_bid_key.clone())? {
+            bids().remove(deps.storage, max_bid_key)?;
+            res = res.add_submessages(prepare_bid_hook(deps.as_ref(), &bid, HookAction::Delete)?);
+        }
+    }
 
     let cw721_transfer_msg = Cw721ExecuteMsg::<Metadata>::TransferNft {
         token_id: ask.token_id.to_string(),
@@ -248,9 +711,11 @@ pub fn execute_remove_ask(
 
     let event = Event::new("remove-ask")
         .add_attribute("collection", collection.to_string())
-        .add_attribute("token_id", token_id.to_string());
+        .add_attribute("token_id", token_id.to_string())
+        .add_attribute("penalty", penalty.to_string());
 
-    Ok(Response::new().add_event(event)
+    Ok(res
+        .add_event(event)
         .add_message(exec_cw721_transfer)
         .add_submessages(hook))
 }
@@ -262,10 +727,11 @@ pub fn execute_update_ask_price(
     info: MessageInfo,
     collection: Addr,
     token_id: TokenId,
-    price: Coin,
+    price: Uint128,
+    denom: Denom,
 ) -> Result<Response, ContractError> {
     nonpayable(&info)?;
-    price_validate(deps.storage, &price)?;
+    price_validate(deps.storage, &collection, price, &denom)?;
 
     let key = ask_key(&collection, &token_id);
 
@@ -277,7 +743,22 @@ pub fn execute_update_ask_price(
         return Err(ContractError::AskExpired {});
     }
 
-    ask.price = price.amount;
+    // The price of a Dutch auction follows its schedule, and the deposits
+    // committed to a sealed auction were checked against its price
+    if matches!(
+        ask.sale_type,
+        SaleType::DutchAuction | SaleType::SealedAuction
+    ) {
+        return Err(ContractError::InvalidSaleType {});
+    }
+
+    // Bids on an auction are escrowed in the denom it was listed in
+    if ask.sale_type == SaleType::Auction && denom != ask.denom {
+        return Err(ContractError::InvalidDenom(denom_key(&denom)));
+    }
+
+    ask.price = price;
+    ask.denom = denom.clone();
     asks().save(deps.storage, key, &ask)?;
 
     let hook = prepare_ask_hook(deps.as_ref(), &ask, HookAction::Update)?;
@@ -286,250 +767,1404 @@ pub fn execute_update_ask_price(
         .add_attribute("collection", collection.to_string())
         .add_attribute("token_id", token_id.to_string())
         .add_attribute("price", price.to_string())
+        .add_attribute("denom", denom_key(&denom))
         .add_attribute("time", env.block.time.to_string());
 
     Ok(Response::new().add_event(event).add_submessages(hook))
 }
 
 /// Places a bid on a listed or unlisted NFT. The bid is escrowed in the contract.
+/// A proxy bid on an auction escrows its max price, and bids the min increment
+/// above competing bids up to it.
 pub fn execute_set_bid(
-    deps: DepsMut,
+    mut deps: DepsMut,
     env: Env,
+    bidder: Addr,
+    bid_info: BidInfo,
+    bid_price: Uint128,
+    denom: Denom,
+) -> Result<Response, ContractError> {
+    let BidInfo {
+        collection,
+        token_id,
+        expires,
+        finder,
+        proxy,
+    } = bid_info;
+    let params = load_params(deps.storage, &collection)?;
+
+    collection_validate(deps.storage, &collection)?;
+    price_validate(deps.storage, &collection, bid_price, &denom)?;
+
+    finder_validate(&finder, &bidder)?;
+
+    let mut res = Response::new();
+    let ask_key = ask_key(&collection, &token_id);
+    let current_bid_key = bid_key(&collection, &token_id, &bidder);
+
+    let existing_ask = asks().may_load(deps.storage, ask_key.clone())?;
+    if let Some(ask) = &existing_ask {
+        if ask.is_expired(&env.block) {
+            return Err(ContractError::AskExpired {});
+        }
+        // Sealed auctions take committed bids instead
+        if ask.sale_type == SaleType::SealedAuction {
+            return Err(ContractError::InvalidSaleType {});
+        }
+        if ask.denom != denom {
+            return Err(ContractError::InvalidDenom(denom_key(&denom)));
+        }
+    }
+    // Proxy bids only compete with other bids on auctions
+    if proxy && !matches!(&existing_ask, Some(ask) if ask.sale_type == SaleType::Auction) {
+        return Err(ContractError::InvalidSaleType {});
+    }
+
+    // Re-entering a bid replaces the previous one
+    let existing_bid = bids().may_load(deps.storage, current_bid_key.clone())?;
+    let action = match &existing_bid {
+        Some(_) => HookAction::Update,
+        None => HookAction::Create,
+    };
+    if let Some(existing_bid) = existing_bid {
+        bids().remove(deps.storage, current_bid_key)?;
+
+        // The leading auction bid is refunded below when it is outbid
+        let is_max_bidder = matches!(
+            &existing_ask,
+            Some(ask) if ask.sale_type == SaleType::Auction && ask.max_bidder.as_ref() == Some(&bidder)
+        );
+        if !is_max_bidder {
+            res = res.add_message(transfer_msg(
+                &existing_bid.denom,
+                existing_bid.price,
+                &existing_bid.bidder,
+            )?);
+        }
+    }
+
+    let save_bid = |store, price, expires_at| -> StdResult<_> {
+        let bid = Bid {
+            finder: finder.clone(),
+            ..Bid::new(
+                collection.clone(),
+                token_id.clone(),
+                bidder.clone(),
+                price,
+                denom.clone(),
+                env.block.time,
+                expires_at,
+            )
+        };
+        store_bid(store, &bid)?;
+        Ok(Some(bid))
+    };
+
+    let mut auction_end = None;
+    let mut shown_price = bid_price;
+    let bid = match existing_ask {
+        Some(mut ask) if ask.sale_type == SaleType::Auction => {
+            // If the bid price is lower than the required one, it fails
+            if ask.price > bid_price {
+                return Err(ContractError::PriceTooSmall(bid_price));
+            }
+
+            if ask.max_bid.is_none() || ask.max_bidder.is_none() {
+                return Err(ContractError::WrongAskInfo {});
+            }
+
+            // A bid of the buy now price wins the auction right away
+            let buy_now = !proxy && ask.buy_now_price == Some(bid_price);
+            if matches!(ask.buy_now_price, Some(buy_now_price) if bid_price > buy_now_price) {
+                return Err(ContractError::InvalidPrice {});
+            }
+
+            let min_bid = min_next_bid(&params, &ask, &env.contract.address);
+            if bid_price < min_bid && !buy_now {
+                return Err(ContractError::BidIncrementTooSmall(min_bid));
+            }
+
+            let max_bidder = ask.max_bidder.clone().unwrap();
+            let max_bid = ask.max_bid.unwrap();
+            // The leading bid escrows its max price when it is a proxy bid
+            let max_price = PROXY_CAPS
+                .may_load(deps.storage, ask_key.clone())?
+                .unwrap_or(max_bid);
+            let increment = ask
+                .min_bid_increment
+                .clone()
+                .unwrap_or_else(|| params.min_bid_increment.clone());
+            let has_rival = max_bidder != env.contract.address && max_bidder != bidder;
+            // The leading proxy bid outbids the new bid up to its max price,
+            // and wins a tie since it was placed first
+            let outbid = has_rival && bid_price <= max_price && !buy_now;
+
+            if !outbid && max_bidder != env.contract.address {
+                res = res.add_message(transfer_msg(&ask.denom, max_price, &max_bidder)?);
+
+                // The outbid bid was refunded, so its record is removed
+                let prev_bid_key = bid_key(&collection, &token_id, &max_bidder);
+                let prev_bid = bids().may_load(deps.storage, prev_bid_key.clone())?;
+
+                if let Some(prev_bid) = prev_bid {
+                    bids().remove(deps.storage, prev_bid_key)?;
+                    res = res.add_submessages(prepare_bid_hook(
+                        deps.as_ref(),
+                        &prev_bid,
+                        HookAction::Delete,
+                    )?);
+                }
+                PROXY_CAPS.remove(deps.storage, ask_key.clone());
+            }
+
+            if buy_now {
+                remove_ask(deps.storage, ask_key.clone())?;
+                HIDDEN_RESERVES.remove(deps.storage, ask_key);
+                ask.price = bid_price;
+                let hook = prepare_ask_hook(deps.as_ref(), &ask, HookAction::Delete)?;
+                res = res.add_submessages(hook);
+                finalize_sale(
+                    deps.branch(),
+                    ask,
+                    bid_price,
+                    bidder.clone(),
+                    finder.clone(),
+                    &mut res,
+                )?;
+                None
+            } else {
+                let new_max_bid = if outbid {
+                    res = res.add_message(transfer_msg(&denom, bid_price, &bidder)?);
+
+                    ask.max_bid = Some(max_price.min(bid_price + increment.over(bid_price)));
+                    None
+                } else {
+                    // A proxy bid only bids what it takes to lead
+                    let price = match (proxy, has_rival) {
+                        (false, _) => bid_price,
+                        (true, true) => {
+                            bid_price.min(min_bid.max(max_price + increment.over(max_price)))
+                        }
+                        (true, false) if max_bidder == bidder => max_bid,
+                        (true, false) => min_bid,
+                    };
+                    if price < bid_price {
+                        PROXY_CAPS.save(deps.storage, ask_key.clone(), &bid_price)?;
+                    }
+                    ask.max_bid = Some(price);
+                    ask.max_bidder = Some(bidder.clone());
+                    shown_price = price;
+                    Some(price)
+                };
+
+                // A late bid extends the auction so other bidders have time to respond
+                let extension = auction_extension(&params, &ask, &env);
+                if extension > 0 {
+                    ask.expires_at = ask.expires_at.plus_seconds(extension);
+                    ask.total_extension += extension;
+                    res = res.add_submessages(prepare_ask_hook(
+                        deps.as_ref(),
+                        &ask,
+                        HookAction::Update,
+                    )?);
+                }
+                asks().save(deps.storage, ask_key, &ask)?;
+                auction_end = Some(ask.expires_at);
+
+                match new_max_bid {
+                    Some(price) => save_bid(deps.storage, price, ask.expires_at)?,
+                    None => {
+                        // The leading bid follows the price and end of the auction
+                        let leading_bid_key = bid_key(&collection, &token_id, &max_bidder);
+                        if let Some(mut leading_bid) =
+                            bids().may_load(deps.storage, leading_bid_key)?
+                        {
+                            leading_bid.price = ask.max_bid.unwrap();
+                            leading_bid.expires_at = ask.expires_at;
+                            store_bid(deps.storage, &leading_bid)?;
+                            res = res.add_submessages(prepare_bid_hook(
+                                deps.as_ref(),
+                                &leading_bid,
+                                HookAction::Update,
+                            )?);
+                        }
+                        None
+                    }
+                }
+            }
+        }
+        Some(mut ask) if ask.sale_type == SaleType::DutchAuction => {
+            // The first bid paying the current price buys the NFT, and any excess is refunded
+            let price = ask.dutch_auction_price(env.block.time).unwrap_or(ask.price);
+            if bid_price < price {
+                return Err(ContractError::PriceTooSmall(bid_price));
+            }
+            if bid_price > price {
+                res = res.add_message(transfer_msg(&denom, bid_price - price, &bidder)?);
+            }
+
+            remove_ask(deps.storage, ask_key)?;
+            ask.price = price;
+            res = res.add_submessages(prepare_ask_hook(deps.as_ref(), &ask, HookAction::Delete)?);
+            finalize_sale(
+                deps.branch(),
+                ask,
+                price,
+                bidder.clone(),
+                finder.clone(),
+                &mut res,
+            )?;
+            None
+        }
+        Some(ask) if ask.price == bid_price => {
+            remove_ask(deps.storage, ask_key)?;
+            res = res.add_submessages(prepare_ask_hook(deps.as_ref(), &ask, HookAction::Delete)?);
+            finalize_sale(
+                deps.branch(),
+                ask,
+                bid_price,
+                bidder.clone(),
+                finder.clone(),
+                &mut res,
+            )?;
+            None
+        }
+        Some(ask) if ask.price < bid_price => {
+            return Err(ContractError::InvalidPrice {});
+        }
+        // Any other bid is an offer that the NFT owner may accept before it expires
+        _ => {
+            let expires = expires.ok_or(ContractError::InvalidDuration {})?;
+            params.bid_expiry.is_valid(expires)?;
+
+            save_bid(
+                deps.storage,
+                bid_price,
+                env.block.time.plus_seconds(expires),
+            )?
+        }
+    };
+
+    let hook = if let Some(bid) = bid {
+        prepare_bid_hook(deps.as_ref(), &bid, action)?
+    } else {
+        vec![]
+    };
+
+    let mut event = Event::new("set-bid")
+        .add_attribute("collection", collection.to_string())
+        .add_attribute("token_id", token_id.to_string())
+        .add_attribute("bidder", bidder)
+        .add_attribute("bid_price", shown_price.to_string())
+        .add_attribute("denom", denom_key(&denom))
+        .add_attribute("time", env.block.time.to_string());
+    if let Some(auction_end) = auction_end {
+        event = event.add_attribute("expires_at", auction_end.to_string());
+    }
+
+    Ok(res.add_submessages(hook).add_event(event))
+}
+
+/// Min price of the next bid on an auction. The first bid must meet the starting
+/// price, and later bids must raise the highest bid by the min bid increment.
+pub fn min_next_bid(params: &SudoParams, ask: &Ask, contract: &Addr) -> Uint128 {
+    let max_bid = ask.max_bid.unwrap_or_default();
+    if ask.max_bidder.as_ref() == Some(contract) {
+        return ask.price.max(max_bid + Uint128::new(1));
+    }
+
+    let increment = ask
+        .min_bid_increment
+        .as_ref()
+        .unwrap_or(&params.min_bid_increment);
+    max_bid + increment.over(max_bid)
+}
+
+/// Seconds a bid placed now extends the auction by, when it falls within the
+/// extension window, capped by the max total extension
+fn auction_extension(params: &SudoParams, ask: &Ask, env: &Env) -> u64 {
+    let remaining = ask
+        .expires_at
+        .seconds()
+        .saturating_sub(env.block.time.seconds());
+    if remaining >= params.extension_window {
+        return 0;
+    }
+
+    params
+        .extension_duration
+        .min(params.max_extension.saturating_sub(ask.total_extension))
+}
+
+/// Commitment to a sealed bid, the SHA-256 hash of `{bidder}:{price}:{salt}`
+pub fn sealed_bid_commitment(bidder: &Addr, price: Uint128, salt: &str) -> Binary {
+    let preimage = format!("{}:{}:{}", bidder, price, salt);
+    Binary::from(Sha256::digest(preimage.as_bytes()).to_vec())
+}
+
+/// Commits to a bid on a sealed auction during its commit phase. The deposit is
+/// escrowed in the contract, and hides the bid as long as it covers it.
+pub fn execute_commit_bid(
+    deps: DepsMut,
+    env: Env,
+    bidder: Addr,
+    commit_info: CommitBidInfo,
+    deposit: Uint128,
+    denom: Denom,
+) -> Result<Response, ContractError> {
+    let CommitBidInfo {
+        collection,
+        token_id,
+        commitment,
+    } = commit_info;
+
+    collection_validate(deps.storage, &collection)?;
+    price_validate(deps.storage, &collection, deposit, &denom)?;
+
+    let ask = asks()
+        .may_load(deps.storage, ask_key(&collection, &token_id))?
+        .ok_or(ContractError::AskNotFound {})?;
+    if ask.sale_type != SaleType::SealedAuction {
+        return Err(ContractError::InvalidSaleType {});
+    }
+    if ask.sealed_auction_phase(env.block.time) != Some(SealedAuctionPhase::Commit) {
+        return Err(ContractError::InvalidAuctionPhase {});
+    }
+    if ask.denom != denom {
+        return Err(ContractError::InvalidDenom(denom_key(&denom)));
+    }
+    if deposit < ask.price {
+        return Err(ContractError::PriceTooSmall(deposit));
+    }
+    if commitment.len() != 32 {
+        return Err(ContractError::InvalidCommitment {});
+    }
+
+    let mut res = Response::new();
+    let key = bid_key(&collection, &token_id, &bidder);
+
+    // Committing again replaces the previous commitment and refunds its deposit
+    match SEALED_BIDS.may_load(deps.storage, key.clone())? {
+        Some(existing_bid) => {
+            res = res.add_message(transfer_msg(
+                &existing_bid.denom,
+                existing_bid.deposit,
+                &existing_bid.bidder,
+            )?);
+        }
+        None => {
+            let commitments = SEALED_BIDS
+                .prefix((collection.clone(), token_id.clone()))
+                .keys_raw(deps.storage, None, None, StorageOrder::Ascending)
+                .take(MAX_SEALED_BIDS)
+                .count();
+            if commitments >= MAX_SEALED_BIDS {
+                return Err(ContractError::TooManySealedBids {});
+            }
+        }
+    }
+
+    let sealed_bid = SealedBid {
+        collection: collection.clone(),
+        token_id: token_id.clone(),
+        bidder: bidder.clone(),
+        commitment,
+        deposit,
+        denom: denom.clone(),
+        time: env.block.time,
+        revealed_price: None,
+    };
+    SEALED_BIDS.save(deps.storage, key, &sealed_bid)?;
+
+    let event = Event::new("commit-bid")
+        .add_attribute("collection", collection.to_string())
+        .add_attribute("token_id", token_id)
+        .add_attribute("bidder", bidder)
+        .add_attribute("deposit", deposit.to_string())
+        .add_attribute("denom", denom_key(&denom))
+        .add_attribute("time", env.block.time.to_string());
+
+    Ok(res.add_event(event))
+}
+
+/// Reveals a committed bid on a sealed auction during its reveal phase.
+/// Bids that are not revealed by the end of the auction forfeit their deposit.
+pub fn execute_reveal_bid(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    collection: Addr,
+    token_id: TokenId,
+    price: Uint128,
+    salt: String,
+) -> Result<Response, ContractError> {
+    nonpayable(&info)?;
+    let bidder = info.sender;
+
+    let ask = asks()
+        .may_load(deps.storage, ask_key(&collection, &token_id))?
+        .ok_or(ContractError::AskNotFound {})?;
+    if ask.sale_type != SaleType::SealedAuction {
+        return Err(ContractError::InvalidSaleType {});
+    }
+    if ask.sealed_auction_phase(env.block.time) != Some(SealedAuctionPhase::Reveal) {
+        return Err(ContractError::InvalidAuctionPhase {});
+    }
+
+    let key = bid_key(&collection, &token_id, &bidder);
+    let mut sealed_bid = SEALED_BIDS.load(deps.storage, key.clone())?;
+    if sealed_bid.revealed_price.is_some() {
+        return Err(ContractError::AlreadyRevealed {});
+    }
+    if sealed_bid_commitment(&bidder, price, &salt) != sealed_bid.commitment {
+        return Err(ContractError::InvalidCommitment {});
+    }
+    if price > sealed_bid.deposit {
+        return Err(ContractError::RevealExceedsDeposit {});
+    }
+
+    sealed_bid.revealed_price = Some(price);
+    SEALED_BIDS.save(deps.storage, key, &sealed_bid)?;
+
+    let event = Event::new("reveal-bid")
+        .add_attribute("collection", collection.to_string())
+        .add_attribute("token_id", token_id)
+        .add_attribute("bidder", bidder)
+        .add_attribute("price", price.to_string())
+        .add_attribute("time", env.block.time.to_string());
+
+    Ok(Response::new().add_event(event))
+}
+
+/// Removes a bid made by the bidder. Bidders can only remove their own bids.
+/// The highest bidder of an auction cannot remove their bid, since the seller
+/// is owed the sale once the auction ends.
+pub fn execute_remove_bid(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    collection: Addr,
+    token_id: TokenId,
+) -> Result<Response, ContractError> {
+    nonpayable(&info)?;
+    let bidder = info.sender;
+
+    let ask = asks().may_load(deps.storage, ask_key(&collection, &token_id))?;
+    if let Some(ask) = ask {
+        if ask.sale_type == SaleType::Auction && ask.max_bidder.as_ref() == Some(&bidder) {
+            return Err(ContractError::MaxBidderNotRemove {});
+        }
+    }
+
+    let key = bid_key(&collection, &token_id, &bidder);
+    let bid = bids().load(deps.storage, key.clone())?;
+    bids().remove(deps.storage, key)?;
+
+    let res = Response::new().add_message(transfer_msg(&bid.denom, bid.price, &bid.bidder)?);
+
+    let hook = prepare_bid_hook(deps.as_ref(), &bid, HookAction::Delete)?;
+
+    let event = Event::new("remove-bid")
+        .add_attribute("collection", collection.to_string())
+        .add_attribute("token_id", token_id.to_string())
+        .add_attribute("bidder", bidder)
+        .add_attribute("time", env.block.time.to_string());
+
+    Ok(res.add_event(event).add_submessages(hook))
+}
+
+/// Seller can accept a bid which transfers funds as well as the token.
+/// Without a bidder, the highest bid of an ended auction is accepted, or of an
+/// ongoing one if the ask allows early acceptance and the reserve is met.
+/// With a bidder, their offer on a fixed price ask is accepted.
+pub fn execute_accept_bid(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    collection: Addr,
+    token_id: TokenId,
+    bidder: Option<Addr>,
+    finder: Option<Addr>,
+) -> Result<Response, ContractError> {
+    nonpayable(&info)?;
+
+    let ask_key = ask_key(&collection, &token_id);
+    let existing_ask = asks()
+        .may_load(deps.storage, ask_key.clone())?
+        .ok_or(ContractError::AskNotFound {})?;
+
+    only_owner_nft(&info, existing_ask.clone().seller)?;
+
+    finder_validate(&finder, &existing_ask.seller)?;
+
+    if let Some(bidder) = bidder {
+        if existing_ask.sale_type != SaleType::FixedPrice {
+            return Err(ContractError::InvalidSaleType {});
+        }
+        remove_ask(deps.storage, ask_key)?;
+        let hook = prepare_ask_hook(deps.as_ref(), &existing_ask, HookAction::Delete)?;
+
+        let res = fill_bid(deps, env, existing_ask, bidder, finder)?;
+        return Ok(res.add_submessages(hook));
+    }
+
+    if existing_ask.sale_type != SaleType::Auction {
+        return Err(ContractError::InvalidSaleType {});
+    }
+
+    let early = !existing_ask.is_expired(&env.block);
+    if early && !existing_ask.allow_early_accept {
+        return Err(ContractError::AuctionNotEnded {});
+    }
+
+    settle_auction(
+        deps,
+        env,
+        existing_ask,
+        finder,
+        Event::new("accept-bid"),
+        early,
+    )
+}
+
+/// Anyone can settle an auction once it has ended, selling the NFT to the highest bidder,
+/// or returning it to the seller if there were no bids or the reserve was not met.
+/// Sealed auctions are settled with their revealed bids.
+pub fn execute_settle_auction(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    collection: Addr,
+    token_id: TokenId,
+) -> Result<Response, ContractError> {
+    nonpayable(&info)?;
+
+    let ask = asks()
+        .may_load(deps.storage, ask_key(&collection, &token_id))?
+        .ok_or(ContractError::AskNotFound {})?;
+
+    match ask.sale_type {
+        SaleType::Auction => {
+            settle_auction(deps, env, ask, None, Event::new("settle-auction"), false)
+        }
+        SaleType::SealedAuction => settle_sealed_auction(deps, env, ask),
+        _ => Err(ContractError::InvalidSaleType {}),
+    }
+}
+
+/// Settles an ended auction and adds the outcome to `event`.
+/// An `early` settlement sells to the highest bid before the auction ends.
+fn settle_auction(
+    mut deps: DepsMut,
+    env: Env,
+    mut ask: Ask,
+    finder: Option<Addr>,
+    event: Event,
+    early: bool,
+) -> Result<Response, ContractError> {
+    if !early && !ask.is_expired(&env.block) {
+        return Err(ContractError::AuctionNotEnded {});
+    }
+
+    let ask_key = ask_key(&ask.collection, &ask.token_id);
+    let reserve_price = match ask.reserve_price {
+        Some(reserve_price) => Some(reserve_price),
+        None => HIDDEN_RESERVES.may_load(deps.storage, ask_key.clone())?,
+    };
+
+    let mut res = Response::new();
+
+    let max_bidder = ask.clone().max_bidder.unwrap();
+    let max_bid = ask.clone().max_bid.unwrap();
+    // The winning bid escrows its max price when it is a proxy bid
+    let max_price = PROXY_CAPS
+        .may_load(deps.storage, ask_key.clone())?
+        .unwrap_or(max_bid);
+
+    // A proxy bid is raised to the reserve price when its max price allows it
+    let reserve_price = reserve_price.unwrap_or_default();
+    let max_bid_price = if max_bid < reserve_price && max_price >= reserve_price {
+        reserve_price
+    } else {
+        max_bid
+    };
+    let reserve_met = max_bid_price >= reserve_price;
+
+    // Accepting early sells the NFT, rather than ending the auction without a sale
+    if early && max_bidder == env.contract.address {
+        return Err(ContractError::AuctionHasNoBids {});
+    }
+    if early && !reserve_met {
+        return Err(ContractError::ReserveNotMet {});
+    }
+
+    remove_ask(deps.storage, ask_key.clone())?;
+    HIDDEN_RESERVES.remove(deps.storage, ask_key.clone());
+    PROXY_CAPS.remove(deps.storage, ask_key);
+
+    if max_bidder != env.contract.address {
+        let winning_bid_key = bid_key(&ask.collection, &ask.token_id, &max_bidder);
+        let winning_bid = bids().may_load(deps.storage, winning_bid_key.clone())?;
+
+        if !reserve_met {
+            // The reserve was not met, so the bid is refunded and the NFT returned to the seller
+            res = res
+                .add_message(transfer_msg(&ask.denom, max_price, &max_bidder)?)
+                .add_message(transfer_nft(&ask.collection, &ask.token_id, &ask.seller)?);
+        } else {
+            if max_price > max_bid_price {
+                res = res.add_message(transfer_msg(
+                    &ask.denom,
+                    max_price - max_bid_price,
+                    &max_bidder,
+                )?);
+            }
+
+            // A finder given by the seller takes precedence over the one given by the bidder
+            let finder = finder.or_else(|| winning_bid.as_ref().and_then(|bid| bid.finder.clone()));
+
+            ask.price = max_bid_price;
+            finalize_sale(
+                deps.branch(),
+                ask.clone(),
+                max_bid_price,
+                max_bidder.clone(),
+                finder,
+                &mut res,
+            )?;
+        }
+
+        // The winning bid is settled or refunded, so its record is removed
+        if let Some(winning_bid) = winning_bid {
+            bids().remove(deps.storage, winning_bid_key)?;
+            res.messages.append(&mut prepare_bid_hook(
+                deps.as_ref(),
+                &winning_bid,
+                HookAction::Delete,
+            )?);
+        }
+    } else {
+        // Without bids the NFT goes back to the seller
+        res = res.add_message(transfer_nft(&ask.collection, &ask.token_id, &ask.seller)?);
+    }
+
+    let hook = prepare_ask_hook(deps.as_ref(), &ask, HookAction::Delete)?;
+
+    let event = event
+        .add_attribute("collection", ask.collection.to_string())
+        .add_attribute("token_id", ask.token_id.to_string())
+        .add_attribute("buyer", max_bidder)
+        .add_attribute("reserve_met", reserve_met.to_string())
+        .add_attribute("time", env.block.time.to_string());
+
+    Ok(res.add_submessages(hook).add_event(event))
+}
+
+/// Settles an ended sealed auction. Revealed bids of at least the ask price compete,
+/// with ties going to the earliest commitment. The winner pays their bid, or the second
+/// highest one in a second price auction, and the rest of their deposit is refunded.
+/// Losing bids are refunded in full, while unrevealed deposits are slashed to the fee recipient.
+fn settle_sealed_auction(mut deps: DepsMut, env: Env, ask: Ask) -> Result<Response, ContractError> {
+    let pricing = match &ask.sealed_auction {
+        Some(sealed_auction) => sealed_auction.pricing.clone(),
+        None => return Err(ContractError::WrongAskInfo {}),
+    };
+    if !ask.is_expired(&env.block) {
+        return Err(ContractError::AuctionNotEnded {});
+    }
+
+    remove_ask(deps.storage, ask_key(&ask.collection, &ask.token_id))?;
+
+    let sealed_bids = SEALED_BIDS
+        .prefix((ask.collection.clone(), ask.token_id.clone()))
+        .range(deps.storage, None, None, StorageOrder::Ascending)
+        .map(|item| item.map(|(_, sealed_bid)| sealed_bid))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let mut res = Response::new();
+    let mut slashed = Uint128::zero();
+    let mut revealed = vec![];
+    for sealed_bid in sealed_bids {
+        SEALED_BIDS.remove(
+            deps.storage,
+            bid_key(
+                &sealed_bid.collection,
+                &sealed_bid.token_id,
+                &sealed_bid.bidder,
+            ),
+        );
+        match sealed_bid.revealed_price {
+            Some(price) if price >= ask.price => revealed.push((price, sealed_bid)),
+            Some(_) => {
+                res = res.add_message(transfer_msg(
+                    &sealed_bid.denom,
+                    sealed_bid.deposit,
+                    &sealed_bid.bidder,
+                )?)
+            }
+            None => slashed += sealed_bid.deposit,
+        }
+    }
+
+    if !slashed.is_zero() {
+        let params = SUDO_PARAMS.load(deps.storage)?;
+        res = res.add_message(transfer_msg(&ask.denom, slashed, &params.fee_recipient)?);
+    }
+
+    let mut event = Event::new("settle-auction")
+        .add_attribute("collection", ask.collection.to_string())
+        .add_attribute("token_id", ask.token_id.to_string());
+
+    // Highest bid first, with ties going to the earliest commitment
+    revealed.sort_by(|(a_price, a), (b_price, b)| b_price.cmp(a_price).then(a.time.cmp(&b.time)));
+
+    if revealed.is_empty() {
+        // Without revealed bids the NFT goes back to the seller
+        res = res.add_message(transfer_nft(&ask.collection, &ask.token_id, &ask.seller)?);
+    } else {
+        let price = match pricing {
+            SealedAuctionPricing::FirstPrice => revealed[0].0,
+            SealedAuctionPricing::SecondPrice => {
+                revealed.get(1).map_or(ask.price, |(price, _)| *price)
+            }
+        };
+        let (_, winner) = revealed.remove(0);
+
+        for (_, sealed_bid) in revealed {
+            res = res.add_message(transfer_msg(
+                &sealed_bid.denom,
+                sealed_bid.deposit,
+                &sealed_bid.bidder,
+            )?);
+        }
+        if winner.deposit > price {
+            res = res.add_message(transfer_msg(
+                &winner.denom,
+                winner.deposit - price,
+                &winner.bidder,
+            )?);
+        }
+
+        finalize_sale(
+            deps.branch(),
+            Ask {
+                price,
+                ..ask.clone()
+            },
+            price,
+            winner.bidder.clone(),
+            None,
+            &mut res,
+        )?;
+
+        event = event
+            .add_attribute("buyer", winner.bidder)
+            .add_attribute("price", price.to_string());
+    }
+
+    let hook = prepare_ask_hook(deps.as_ref(), &ask, HookAction::Delete)?;
+
+    let event = event
+        .add_attribute("slashed", slashed.to_string())
+        .add_attribute("time", env.block.time.to_string());
+
+    Ok(res.add_submessages(hook).add_event(event))
+}
+
+/// Operators can remove expired asks. The NFTs of fixed price asks and Dutch auctions
+/// are returned to their sellers, while auctions are settled.
+pub fn execute_remove_stale_asks(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    collection: Addr,
+    token_ids: Vec<TokenId>,
+) -> Result<Response, ContractError> {
+    nonpayable(&info)?;
+    only_operator(deps.storage, &info)?;
+
+    let mut res = Response::new();
+    for token_id in token_ids {
+        let ask = asks()
+            .may_load(deps.storage, ask_key(&collection, &token_id))?
+            .ok_or(ContractError::AskNotFound {})?;
+        if !ask.is_expired(&env.block) {
+            return Err(ContractError::AskNotExpired {});
+        }
+
+        let mut settled = settle_expired_ask(deps.branch(), &env, ask)?;
+        res.messages.append(&mut settled.messages);
+        res.events.append(&mut settled.events);
+    }
+
+    let event = Event::new("remove-stale-asks")
+        .add_attribute("collection", collection.to_string())
+        .add_attribute("operator", info.sender);
+
+    Ok(res.add_event(event))
+}
+
+/// Anyone can process expired orders, up to `limit` of them, in order of expiration.
+/// Ended auctions are settled, the NFTs of other expired asks are returned, and expired
+/// bids and collection bids are refunded. The caller is paid the keeper reward for each
+/// processed ask of another seller, out of the collected listing fees.
+pub fn execute_process_expired(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    nonpayable(&info)?;
+
+    let limit = limit
+        .unwrap_or(DEFAULT_PROCESS_LIMIT)
+        .min(MAX_PROCESS_LIMIT) as usize;
+    // The expiration indexes are ranged raw, as `MultiIndex::prefix_range` reads the stored
+    // length of the primary key in place of the order
+    let expired = Some(PrefixBound::inclusive(env.block.time.seconds()));
+
+    let mut res = Response::new();
+    let mut processed = 0;
+    // Only asks earn the keeper reward, as bidders get their escrow back in full and
+    // could otherwise collect the reward on their own expired bids at no cost
+    let mut rewarded = 0u128;
+
+    let expired_asks = asks()
+        .idx
+        .expires_at
+        .prefix_range_raw(deps.storage, None, expired.clone(), StorageOrder::Ascending)
+        .filter(|item| !matches!(item, Ok((_, ask)) if !ask.is_expired(&env.block)))
+        .take(limit)
+        .map(|item| item.map(|(_, ask)| ask))
+        .collect::<StdResult<Vec<_>>>()?;
+    for ask in expired_asks {
+        if ask.seller != info.sender {
+            rewarded += 1;
+        }
+        let mut settled = settle_expired_ask(deps.branch(), &env, ask)?;
+        res.messages.append(&mut settled.messages);
+        res.events.append(&mut settled.events);
+        processed += 1;
+    }
+
+    let store: &dyn Storage = deps.storage;
+    let expired_bids = bids()
+        .idx
+        .expires_at
+        .prefix_range_raw(store, None, expired.clone(), StorageOrder::Ascending)
+        .map(|item| {
+            let (_, bid) = item?;
+            // The highest bid of an auction is settled along with the auction
+            let ask = asks().may_load(store, ask_key(&bid.collection, &bid.token_id))?;
+            let skip = !bid.is_expired(&env.block)
+                || matches!(ask, Some(ask) if ask.sale_type == SaleType::Auction
+                    && ask.max_bidder.as_ref() == Some(&bid.bidder));
+            Ok((!skip).then_some(bid))
+        })
+        .filter_map(StdResult::transpose)
+        .take(limit - processed)
+        .collect::<StdResult<Vec<_>>>()?;
+    for bid in expired_bids {
+        bids().remove(
+            deps.storage,
+            bid_key(&bid.collection, &bid.token_id, &bid.bidder),
+        )?;
+        res = res.add_message(transfer_msg(&bid.denom, bid.price, &bid.bidder)?);
+
+        let event = Event::new("remove-expired-bid")
+            .add_attribute("collection", bid.collection.to_string())
+            .add_attribute("token_id", bid.token_id.to_string())
+            .add_attribute("bidder", bid.bidder.to_string());
+        res = res
+            .add_submessages(prepare_bid_hook(deps.as_ref(), &bid, HookAction::Delete)?)
+            .add_event(event);
+        processed += 1;
+    }
+
+    let expired_collection_bids = collection_bids()
+        .idx
+        .expires_at
+        .prefix_range_raw(deps.storage, None, expired, StorageOrder::Ascending)
+        .filter(|item| {
+            !matches!(item, Ok((_, collection_bid)) if !collection_bid.is_expired(&env.block))
+        })
+        .take(limit - processed)
+        .map(|item| item.map(|(_, collection_bid)| collection_bid))
+        .collect::<StdResult<Vec<_>>>()?;
+    for collection_bid in expired_collection_bids {
+        collection_bids().remove(
+            deps.storage,
+            collection_bid_key(&collection_bid.collection, &collection_bid.bidder),
+        )?;
+
+        let event = Event::new("remove-expired-collection-bid")
+            .add_attribute("collection", collection_bid.collection.to_string())
+            .add_attribute("bidder", collection_bid.bidder.to_string());
+        res = res
+            .add_message(transfer_msg(
+                &collection_bid.denom,
+                collection_bid.price,
+                &collection_bid.bidder,
+            )?)
+            .add_submessages(prepare_collection_bid_hook(
+                deps.as_ref(),
+                &collection_bid,
+                HookAction::Delete,
+            )?)
+            .add_event(event);
+        processed += 1;
+    }
+
+    // The keeper reward is limited to the listing fees collected in its denom
+    let params = SUDO_PARAMS.load(deps.storage)?;
+    let reward_denom = params.keeper_reward.denom;
+    let collected = LISTING_FEES
+        .may_load(deps.storage, &reward_denom)?
+        .unwrap_or_default();
+    let reward = params
+        .keeper_reward
+        .amount
+        .saturating_mul(Uint128::from(rewarded))
+        .min(collected);
+    if !reward.is_zero() {
+        LISTING_FEES.save(deps.storage, &reward_denom, &(collected - reward))?;
+        res = res.add_message(transfer_msg(
+            &Denom::Native(reward_denom.clone()),
+            reward,
+            &info.sender,
+        )?);
+    }
+
+    let event = Event::new("process-expired")
+        .add_attribute("keeper", info.sender)
+        .add_attribute("processed", processed.to_string())
+        .add_attribute("reward", coin(reward.u128(), reward_denom).to_string())
+        .add_attribute("time", env.block.time.to_string());
+
+    Ok(res.add_event(event))
+}
+
+/// Settles an expired ask. Auctions are settled, and the NFTs of other asks
+/// are returned to the seller.
+fn settle_expired_ask(deps: DepsMut, env: &Env, ask: Ask) -> Result<Response, ContractError> {
+    match ask.sale_type {
+        SaleType::Auction => settle_auction(
+            deps,
+            env.clone(),
+            ask,
+            None,
+            Event::new("settle-auction"),
+            false,
+        ),
+        SaleType::SealedAuction => settle_sealed_auction(deps, env.clone(), ask),
+        SaleType::FixedPrice | SaleType::DutchAuction => {
+            remove_ask(deps.storage, ask_key(&ask.collection, &ask.token_id))?;
+
+            let hook = prepare_ask_hook(deps.as_ref(), &ask, HookAction::Delete)?;
+
+            let event = Event::new("remove-stale-ask")
+                .add_attribute("collection", ask.collection.to_string())
+                .add_attribute("token_id", ask.token_id.to_string());
+
+            Ok(Response::new()
+                .add_message(transfer_nft(&ask.collection, &ask.token_id, &ask.seller)?)
+                .add_submessages(hook)
+                .add_event(event))
+        }
+    }
+}
+
+/// NFT owner can sell an NFT sent with `SendNft` to an offer on it
+pub fn execute_receive_bid(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    rcv_msg: Cw721ReceiveMsg,
+    bidder: Addr,
+    finder: Option<Addr>,
+    finders_fee_bps: Option<u64>,
+) -> Result<Response, ContractError> {
+    nonpayable(&info)?;
+
+    // The sender of `ReceiveNft` is the collection that now escrows the NFT
+    let collection = info.sender;
+    let seller = deps.api.addr_validate(&rcv_msg.sender)?;
+    finder_validate(&finder, &seller)?;
+
+    if let Some(fee) = finders_fee_bps {
+        let params = SUDO_PARAMS.load(deps.storage)?;
+        if Decimal::percent(fee) > params.max_finders_fee_percent {
+            return Err(ContractError::InvalidFindersFeeBps(fee));
+        }
+    }
+
+    let mut ask = unlisted_ask(collection, rcv_msg.token_id, seller, &env);
+    // Without a listing, the seller sets the finders fee when accepting
+    ask.finders_fee_bps = finders_fee_bps;
+
+    fill_bid(deps, env, ask, bidder, finder)
+}
+
+/// Sells the NFT escrowed for `ask` to the offer of `bidder`
+fn fill_bid(
+    mut deps: DepsMut,
+    env: Env,
+    mut ask: Ask,
+    bidder: Addr,
+    finder: Option<Addr>,
+) -> Result<Response, ContractError> {
+    let key = bid_key(&ask.collection, &ask.token_id, &bidder);
+    let bid = bids().load(deps.storage, key.clone())?;
+    if bid.is_expired(&env.block) {
+        return Err(ContractError::BidExpired {});
+    }
+    bids().remove(deps.storage, key)?;
+
+    ask.price = bid.price;
+    ask.denom = bid.denom.clone();
+
+    // A finder given by the seller takes precedence over the one given by the bidder
+    let finder = finder.or_else(|| bid.finder.clone());
+
+    let mut res = Response::new();
+    finalize_sale(
+        deps.branch(),
+        ask.clone(),
+        bid.price,
+        bidder.clone(),
+        finder,
+        &mut res,
+    )?;
+
+    let hook = prepare_bid_hook(deps.as_ref(), &bid, HookAction::Delete)?;
+
+    let event = Event::new("accept-bid")
+        .add_attribute("collection", ask.collection.to_string())
+        .add_attribute("token_id", ask.token_id.to_string())
+        .add_attribute("buyer", bidder)
+        .add_attribute("price", bid.price.to_string())
+        .add_attribute("time", env.block.time.to_string());
+
+    Ok(res.add_submessages(hook).add_event(event))
+}
+
+/// The creator of a collection can register its royalty, which is paid on sales
+/// when the collection doesn't report one with `GetCollectionState`
+pub fn execute_set_royalty(
+    deps: DepsMut,
     info: MessageInfo,
-    bid_info: BidInfo,
+    collection: Addr,
+    recipient: Addr,
+    royalty_rate: Decimal,
+    shares: Vec<RoyaltyShare>,
 ) -> Result<Response, ContractError> {
-    let BidInfo {
-        collection,
-        token_id,
-    } = bid_info;
-    let params = SUDO_PARAMS.load(deps.storage)?;
+    nonpayable(&info)?;
+    only_collection_creator(deps.as_ref(), &info, &collection)?;
 
-    let bid_price = must_pay(&info, NATIVE_DENOM)?;
-    if bid_price < params.min_price {
-        return Err(ContractError::PriceTooSmall(bid_price));
-    }
+    store_royalty(deps, &collection, recipient.clone(), royalty_rate, shares)?;
 
-    let bidder = info.sender.clone();
-    let mut res = Response::new();
-    let ask_key = ask_key(&collection, &token_id);
-    let current_bid_key = bid_key(&collection, &token_id, &bidder);
+    let event = Event::new("set-royalty")
+        .add_attribute("collection", collection.to_string())
+        .add_attribute("recipient", recipient)
+        .add_attribute("royalty_rate", royalty_rate.to_string());
 
-    let existing_bid = bids().may_load(deps.storage, current_bid_key.clone())?;
-    if existing_bid.is_some() {
-        bids().remove(deps.storage, current_bid_key)?;
-    }
+    Ok(Response::new().add_event(event))
+}
 
-    let existing_ask = asks().may_load(deps.storage, ask_key.clone())?;
+/// The creator of a collection can remove its royalty from the royalty registry
+pub fn execute_remove_royalty(
+    deps: DepsMut,
+    info: MessageInfo,
+    collection: Addr,
+) -> Result<Response, ContractError> {
+    nonpayable(&info)?;
+    only_collection_creator(deps.as_ref(), &info, &collection)?;
 
-    // if there is no ask
-    // return an error
-    if existing_ask.is_none() {
-        return Err(ContractError::AskNotFound {});
+    if !ROYALTIES.has(deps.storage, &collection) {
+        return Err(ContractError::CollectionNotFound(collection.to_string()));
     }
+    ROYALTIES.remove(deps.storage, &collection);
 
-    let mut ask = existing_ask.unwrap();
+    let event = Event::new("remove-royalty").add_attribute("collection", collection.to_string());
 
-    if ask.is_expired(&env.block) {
-        return Err(ContractError::AskExpired {});
-    }
+    Ok(Response::new().add_event(event))
+}
 
-    // If the bid price is lower than the required one, it fails
-    if ask.sale_type == SaleType::Auction && ask.price > bid_price {
-        return Err(ContractError::PriceTooSmall(bid_price));
+/// Stores the royalty of a collection, which is limited to the max royalty rate
+pub fn store_royalty(
+    deps: DepsMut,
+    collection: &Addr,
+    recipient: Addr,
+    royalty_rate: Decimal,
+    shares: Vec<RoyaltyShare>,
+) -> Result<(), ContractError> {
+    let params = SUDO_PARAMS.load(deps.storage)?;
+    if royalty_rate > params.max_royalty_rate {
+        return Err(ContractError::InvalidRoyaltyRate(royalty_rate));
     }
-    
-    let save_bid = |store| -> StdResult<_> {
-        let bid = Bid::new(
-            collection.clone(),
-            token_id.clone(),
-            bidder.clone(),
-            bid_price,
-            true,
-            env.block.time,
-        );
-        store_bid(store, &bid)?;
-        Ok(Some(bid))
-    };
 
-    let bid = match ask.sale_type {
-        SaleType::FixedPrice => {
-            if ask.price != bid_price {
-                return Err(ContractError::InvalidPrice {});
+    let shares = shares
+        .into_iter()
+        .map(|share| {
+            if share.weight == 0 {
+                return Err(ContractError::InvalidRoyaltyShares {});
             }
-            asks().remove(deps.storage, ask_key)?;
-            finalize_sale(
-                deps.as_ref(),
-                ask,
-                bid_price,
-                bidder.clone(),
-                // finder,
-                &mut res,
-            )?;
-            None
+            Ok(cw721_base::state::RoyaltyShare {
+                address: deps.api.addr_validate(&share.address)?,
+                weight: share.weight,
+            })
+        })
+        .collect::<Result<Vec<_>, ContractError>>()?;
+
+    ROYALTIES.save(
+        deps.storage,
+        collection,
+        &RoyaltyInfo {
+            recipient,
+            royalty_rate,
+            shares,
         },
-        SaleType::Auction => {
-            if ask.max_bid.is_none() || ask.max_bidder.is_none() {
-                return Err(ContractError::WrongAskInfo {});
-            }
+    )?;
 
-            if bid_price <= ask.max_bid.unwrap() {
-                return Err(ContractError::InsufficientFundsSend {});
-            }
+    Ok(())
+}
 
-            let max_bidder = ask.max_bidder.unwrap();
+/// Places a bid on any NFT in a collection. The bid is escrowed in the contract.
+pub fn execute_set_collection_bid(
+    deps: DepsMut,
+    env: Env,
+    bidder: Addr,
+    collection_bid_info: CollectionBidInfo,
+    price: Uint128,
+    denom: Denom,
+) -> Result<Response, ContractError> {
+    let CollectionBidInfo {
+        collection,
+        finders_fee_bps,
+        expires,
+    } = collection_bid_info;
+    let params = load_params(deps.storage, &collection)?;
 
-            let refund_msg = BankMsg::Send {
-                to_address: max_bidder.to_string(),
-                amount: vec![coin(ask.max_bid.unwrap().u128(), NATIVE_DENOM)],
-            };
+    collection_validate(deps.storage, &collection)?;
+    price_validate(deps.storage, &collection, price, &denom)?;
+    params.bid_expiry.is_valid(expires)?;
 
-            if max_bidder != env.contract.address {
-                res = res.add_message(refund_msg);
-                
-                let prev_bid_key = bid_key(&collection, &token_id, &max_bidder);
-                let prev_bid = bids().may_load(deps.storage, prev_bid_key.clone())?;
+    if let Some(fee) = finders_fee_bps {
+        if Decimal::percent(fee) > params.max_finders_fee_percent {
+            return Err(ContractError::InvalidFindersFeeBps(fee));
+        }
+    }
 
-                if let Some(mut prev_bid) = prev_bid {
-                    prev_bid.active = false;
-                    bids().save(deps.storage, prev_bid_key, &prev_bid)?;
-                }
-            }
-                
-            ask.max_bid = Some(bid_price);
-            ask.max_bidder = Some(info.sender);
-            asks().save(deps.storage, ask_key, &ask)?;
+    let mut res = Response::new();
 
-            save_bid(deps.storage)?
-        }
+    let key = collection_bid_key(&collection, &bidder);
+
+    // Replacing a bid refunds the previously escrowed funds
+    let existing_bid = collection_bids().may_load(deps.storage, key.clone())?;
+    let action = if let Some(existing_bid) = existing_bid {
+        res = res.add_message(transfer_msg(
+            &existing_bid.denom,
+            existing_bid.price,
+            &existing_bid.bidder,
+        )?);
+        HookAction::Update
+    } else {
+        HookAction::Create
     };
 
-    let hook = if let Some(bid) = bid {
-        prepare_bid_hook(deps.as_ref(), &bid, HookAction::Create)?
-    } else {
-        vec![]
+    let collection_bid = CollectionBid {
+        collection: collection.clone(),
+        bidder: bidder.clone(),
+        price,
+        denom: denom.clone(),
+        finders_fee_bps,
+        expires_at: env.block.time.plus_seconds(expires),
     };
+    collection_bids().save(deps.storage, key, &collection_bid)?;
+
+    let hook = prepare_collection_bid_hook(deps.as_ref(), &collection_bid, action)?;
 
-    let event = Event::new("set-bid")
+    let event = Event::new("set-collection-bid")
         .add_attribute("collection", collection.to_string())
-        .add_attribute("token_id", token_id.to_string())
         .add_attribute("bidder", bidder)
-        .add_attribute("bid_price", bid_price.to_string())
+        .add_attribute("bid_price", price.to_string())
+        .add_attribute("denom", denom_key(&denom))
+        .add_attribute("expires", expires.to_string())
         .add_attribute("time", env.block.time.to_string());
 
     Ok(res.add_submessages(hook).add_event(event))
 }
 
-/// Removes a bid made by the bidder. Bidders can only remove their own bids
-// pub fn execute_remove_bid(
-//     deps: DepsMut,
-//     _env: Env,
-//     info: MessageInfo,
-//     collection: Addr,
-//     token_id: TokenId,
-// ) -> Result<Response, ContractError> {
-//     nonpayable(&info)?;
-//     let bidder = info.sender;
-
-//     let key = bid_key(&collection, &token_id, &bidder);
-//     let bid = bids().load(deps.storage, key.clone())?;
-//     bids().remove(deps.storage, key)?;
+/// Removes a collection bid made by the bidder. Bidders can only remove their own bids
+pub fn execute_remove_collection_bid(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    collection: Addr,
+) -> Result<Response, ContractError> {
+    nonpayable(&info)?;
+    let bidder = info.sender;
 
-//     let refund_bidder_msg = BankMsg::Send {
-//         to_address: bid.bidder.to_string(),
-//         amount: vec![coin(bid.price.u128(), NATIVE_DENOM)],
-//     };
+    let key = collection_bid_key(&collection, &bidder);
+    let collection_bid = collection_bids().load(deps.storage, key.clone())?;
+    collection_bids().remove(deps.storage, key)?;
 
-//     let hook = prepare_bid_hook(deps.as_ref(), &bid, HookAction::Delete)?;
+    let refund_bidder_msg = transfer_msg(
+        &collection_bid.denom,
+        collection_bid.price,
+        &collection_bid.bidder,
+    )?;
 
-//     let event = Event::new("remove-bid")
-//         .add_attribute("collection", collection)
-//         .add_attribute("token_id", token_id.to_string())
-//         .add_attribute("bidder", bidder);
+    let hook = prepare_collection_bid_hook(deps.as_ref(), &collection_bid, HookAction::Delete)?;
 
-//     let res = Response::new()
-//         .add_message(refund_bidder_msg)
-//         .add_event(event)
-//         .add_submessages(hook);
+    let event = Event::new("remove-collection-bid")
+        .add_attribute("collection", collection.to_string())
+        .add_attribute("bidder", bidder)
+        .add_attribute("time", env.block.time.to_string());
 
-//     Ok(res)
-// }
+    Ok(Response::new()
+        .add_message(refund_bidder_msg)
+        .add_event(event)
+        .add_submessages(hook))
+}
 
-// Seller can accept a bid which transfers funds as well as the token. The bid may or may not be associated with an ask.
-pub fn execute_accept_bid(
+/// Seller of a fixed price ask can sell the escrowed NFT to a collection bid
+pub fn execute_accept_collection_bid(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
     collection: Addr,
     token_id: TokenId,
+    bidder: Addr,
+    finder: Option<Addr>,
 ) -> Result<Response, ContractError> {
     nonpayable(&info)?;
 
-    let ask_key = ask_key(&collection, &token_id);
-    let existing_ask = asks().may_load(deps.storage, ask_key.clone())?.unwrap();
+    let key = ask_key(&collection, &token_id);
+    let ask = asks()
+        .may_load(deps.storage, key.clone())?
+        .ok_or(ContractError::AskNotFound {})?;
 
-    only_owner_nft(&info, existing_ask.clone().seller)?;
+    only_owner_nft(&info, ask.seller.clone())?;
 
-    if !existing_ask.is_expired(&env.block) {
-        return Err(ContractError::AuctionNotEnded {});
+    finder_validate(&finder, &ask.seller)?;
+
+    if ask.sale_type != SaleType::FixedPrice {
+        return Err(ContractError::InvalidSaleType {});
     }
 
-    asks().remove(deps.storage, ask_key)?;
- 
+    remove_ask(deps.storage, key)?;
+    let hook = prepare_ask_hook(deps.as_ref(), &ask, HookAction::Delete)?;
+
+    let res = fill_collection_bid(deps, env, ask, bidder, finder)?;
+    Ok(res.add_submessages(hook))
+}
 
-    let mut res = Response::new();
+/// NFT owner can sell an NFT sent with `SendNft` to a collection bid
+pub fn execute_receive_collection_bid(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    rcv_msg: Cw721ReceiveMsg,
+    bidder: Addr,
+    finder: Option<Addr>,
+) -> Result<Response, ContractError> {
+    nonpayable(&info)?;
 
-    let max_bidder = existing_ask.clone().max_bidder.unwrap();
-    let max_bid_price = existing_ask.clone().max_bid.unwrap();
+    // The sender of `ReceiveNft` is the collection that now escrows the NFT
+    let collection = info.sender;
+    let seller = deps.api.addr_validate(&rcv_msg.sender)?;
+    finder_validate(&finder, &seller)?;
 
-    if max_bidder != env.contract.address {
-        finalize_sale(
-            deps.as_ref(),
-            existing_ask.clone(),
-            max_bid_price,
-            max_bidder.clone(),
-            // finder,
-            &mut res,
-        )?;
-    } else {
-        let cw721_transfer_msg = Cw721ExecuteMsg::<Metadata>::TransferNft {
-            token_id: token_id.to_string(),
-            recipient: existing_ask.seller.to_string(),
-        };
-    
-        let exec_cw721_transfer = WasmMsg::Execute {
-            contract_addr: collection.to_string(),
-            msg: to_binary(&cw721_transfer_msg)?,
-            funds: vec![],
-        };
+    let ask = unlisted_ask(collection, rcv_msg.token_id, seller, &env);
+
+    fill_collection_bid(deps, env, ask, bidder, finder)
+}
 
-        res.clone().add_message(exec_cw721_transfer);
+/// Sells the NFT escrowed for `ask` to the collection bid of `bidder`
+fn fill_collection_bid(
+    mut deps: DepsMut,
+    env: Env,
+    mut ask: Ask,
+    bidder: Addr,
+    finder: Option<Addr>,
+) -> Result<Response, ContractError> {
+    let key = collection_bid_key(&ask.collection, &bidder);
+    let collection_bid = collection_bids().load(deps.storage, key.clone())?;
+    if collection_bid.is_expired(&env.block) {
+        return Err(ContractError::BidExpired {});
     }
+    collection_bids().remove(deps.storage, key)?;
 
-    let event = Event::new("accept-bid")
-        .add_attribute("collection", collection.to_string())
-        .add_attribute("token_id", token_id.to_string())
-        .add_attribute("buyer", max_bidder)
+    // Collection bids pay the finders fee offered by the bidder
+    ask.price = collection_bid.price;
+    ask.denom = collection_bid.denom.clone();
+    ask.finders_fee_bps = collection_bid.finders_fee_bps;
+
+    let mut res = Response::new();
+    finalize_sale(
+        deps.branch(),
+        ask.clone(),
+        collection_bid.price,
+        bidder.clone(),
+        finder,
+        &mut res,
+    )?;
+
+    let hook = prepare_collection_bid_hook(deps.as_ref(), &collection_bid, HookAction::Delete)?;
+
+    let event = Event::new("accept-collection-bid")
+        .add_attribute("collection", ask.collection.to_string())
+        .add_attribute("token_id", ask.token_id.to_string())
+        .add_attribute("bidder", bidder)
+        .add_attribute("seller", ask.seller.to_string())
+        .add_attribute("price", collection_bid.price.to_string())
         .add_attribute("time", env.block.time.to_string());
 
-    Ok(res.add_event(event))
+    Ok(res.add_submessages(hook).add_event(event))
+}
+
+/// Builds an ask for an NFT that was escrowed without being listed,
+/// so it can be settled through `finalize_sale`
+fn unlisted_ask(collection: Addr, token_id: TokenId, seller: Addr, env: &Env) -> Ask {
+    Ask {
+        sale_type: SaleType::FixedPrice,
+        collection,
+        token_id,
+        img_url: String::new(),
+        seller,
+        price: Uint128::zero(),
+        denom: Denom::Native(String::new()),
+        funds_recipient: None,
+        finders_fee_bps: None,
+        expires_at: env.block.time,
+        max_bid: None,
+        max_bidder: None,
+        total_extension: 0,
+        min_bid_increment: None,
+        reserve_price: None,
+        buy_now_price: None,
+        allow_early_accept: false,
+        dutch_auction: None,
+        sealed_auction: None,
+        listing_fee: None,
+        created_at: env.block.time,
+    }
 }
+
 /// Transfers funds and NFT, updates bid
 fn finalize_sale(
-    deps: Deps,
+    deps: DepsMut,
     ask: Ask,
     price: Uint128,
     buyer: Addr,
+    finder: Option<Addr>,
     res: &mut Response,
 ) -> StdResult<()> {
-    payout(
-        deps,
-        ask.collection.clone(),
-        price,
-        ask.funds_recipient
-            .clone()
-            .unwrap_or_else(|| ask.seller.clone()),
-        res,
-    )?;
+    refund_listing_fee(deps.storage, &ask, res)?;
+
+    let deps = deps.as_ref();
+    let network_fee = payout(deps, &ask, price, finder, res)?;
 
     let cw721_transfer_msg = Cw721ExecuteMsg::<Metadata>::TransferNft {
         token_id: ask.token_id.to_string(),
@@ -551,87 +2186,235 @@ fn finalize_sale(
         .add_attribute("token_id", ask.token_id.to_string())
         .add_attribute("seller", ask.seller.to_string())
         .add_attribute("buyer", buyer.to_string())
-        .add_attribute("price", price.to_string());
+        .add_attribute("price", price.to_string())
+        .add_attribute("denom", denom_key(&ask.denom))
+        .add_attribute("trading_fee", network_fee.to_string());
+
+    res.events.push(event);
+
+    Ok(())
+}
+
+/// Refunds the listing fee of a sold ask to the seller, if enabled by governance.
+/// The refund is limited to the listing fees left after withdrawals.
+fn refund_listing_fee(store: &mut dyn Storage, ask: &Ask, res: &mut Response) -> StdResult<()> {
+    let params = SUDO_PARAMS.load(store)?;
+    let listing_fee = match &ask.listing_fee {
+        Some(listing_fee) if params.refund_listing_fee_on_sale => listing_fee,
+        _ => return Ok(()),
+    };
+
+    let collected = LISTING_FEES
+        .may_load(store, &listing_fee.denom)?
+        .unwrap_or_default();
+    let refund = listing_fee.amount.min(collected);
+    if refund.is_zero() {
+        return Ok(());
+    }
+    LISTING_FEES.save(store, &listing_fee.denom, &(collected - refund))?;
+
+    res.messages.push(SubMsg::new(transfer_msg(
+        &Denom::Native(listing_fee.denom.clone()),
+        refund,
+        &ask.seller,
+    )?));
 
+    let event = Event::new("refund-listing-fee")
+        .add_attribute("collection", ask.collection.to_string())
+        .add_attribute("token_id", ask.token_id.to_string())
+        .add_attribute("seller", ask.seller.to_string())
+        .add_attribute("amount", refund.to_string())
+        .add_attribute("denom", listing_fee.denom.clone());
     res.events.push(event);
 
     Ok(())
 }
 
-/// Payout a bid
+/// Payout a bid in the denom of the ask. The trading fee is taken first, and
+/// royalties are paid from the remainder. The finders fee is paid from the seller's share.
+/// Returns the trading fee amount.
 fn payout(
     deps: Deps,
-    collection: Addr,
+    ask: &Ask,
     payment: Uint128,
-    payment_recipient: Addr,
-    // finder: Option<Addr>,
-    // finders_fee_bps: Option<u64>,
+    finder: Option<Addr>,
     res: &mut Response,
-) -> StdResult<()> {
-    // let params = SUDO_PARAMS.load(deps.storage)?;
+) -> StdResult<Uint128> {
+    let params = load_params(deps.storage, &ask.collection)?;
 
-    // Append Fair Burn message
-    // let network_fee = payment * params.trading_fee_percent / Uint128::from(100u128);
-    // fair_burn(network_fee.u128(), None, res);
+    let network_fee = payment * params.trading_fee_percent / Uint128::from(100u128);
+    if network_fee > payment {
+        return Err(StdError::generic_err("Fees exceed payment"));
+    }
+    if !network_fee.is_zero() {
+        res.messages.push(SubMsg::new(transfer_msg(
+            &ask.denom,
+            network_fee,
+            &params.fee_recipient,
+        )?));
+    }
+    let mut seller_amount = payment - network_fee;
 
-    let collection_info: CollectionInfoResponse = deps
+    // Collections that don't report a valid royalty fall back to the royalty registry
+    let royalty = deps
         .querier
-        .query_wasm_smart(collection.clone(), &Cw721QueryMsg::GetCollectionState  {})?;
+        .query_wasm_smart::<CollectionInfoResponse>(
+            ask.collection.clone(),
+            &Cw721QueryMsg::GetCollectionState {},
+        )
+        .ok()
+        .and_then(|collection_info| collection_info.royalty_info)
+        .filter(|royalty| royalty.royalty_rate_validate(deps.api).is_ok());
+    let royalty = match royalty {
+        Some(royalty) => Some(royalty),
+        None => ROYALTIES
+            .may_load(deps.storage, &ask.collection)?
+            .map(|royalty| Royalty {
+                address: royalty.recipient.to_string(),
+                royalty_rate: royalty.royalty_rate,
+                shares: royalty
+                    .shares
+                    .into_iter()
+                    .map(|share| RoyaltyShare {
+                        address: share.address.to_string(),
+                        weight: share.weight,
+                    })
+                    .collect(),
+            }),
+    };
 
+    // If token supports royalities, payout shares to royalty recipients
+    if let Some(royalty) = royalty {
+        // Royalties are capped so they cannot drain the seller's share
+        let amount = seller_amount * royalty.royalty_rate.min(params.max_royalty_rate);
+        if seller_amount < amount {
+            return Err(StdError::generic_err("Fees exceed payment"));
+        }
 
-    match collection_info.royalty_info {
-        // If token supports royalities, payout shares to royalty recipient
-        Some(royalty) => {
-            let amount = coin((payment * royalty.royalty_rate).u128(), NATIVE_DENOM);
-            if payment < amount.amount {
-                return Err(StdError::generic_err("Fees exceed payment"));
+        for (recipient, share_amount) in royalty.split(amount) {
+            if !share_amount.is_zero() {
+                res.messages.push(SubMsg::new(transfer_msg(
+                    &ask.denom,
+                    share_amount,
+                    &recipient,
+                )?));
             }
-            res.messages.push(SubMsg::new(BankMsg::Send {
-                to_address: royalty.address.to_string(),
-                amount: vec![amount.clone()],
-            }));
 
             let event = Event::new("royalty-payout")
-                .add_attribute("collection", collection.to_string())
-                .add_attribute("amount", amount.to_string())
-                .add_attribute("recipient", royalty.address.to_string());
+                .add_attribute("collection", ask.collection.to_string())
+                .add_attribute("amount", share_amount.to_string())
+                .add_attribute("denom", denom_key(&ask.denom))
+                .add_attribute("recipient", recipient);
             res.events.push(event);
+        }
+
+        seller_amount -= amount;
+    }
 
-            let seller_share_msg = BankMsg::Send {
-                to_address: payment_recipient.to_string(),
-                amount: vec![coin(
-                    (payment * (Decimal::one() - royalty.royalty_rate)).u128(),
-                    NATIVE_DENOM.to_string(),
-                )],
-            };
-            res.messages.push(SubMsg::new(seller_share_msg));
+    if let (Some(finder), Some(fee_bps)) = (finder, ask.finders_fee_bps) {
+        let finders_fee = payment * Decimal::percent(fee_bps) / Uint128::from(100u128);
+        if seller_amount < finders_fee {
+            return Err(StdError::generic_err("Fees exceed payment"));
         }
-        None => {
-            // if payment < network_fee {
-            //     return Err(StdError::generic_err("Fees exceed payment"));
-            // }
-            // If token doesn't support royalties, pay seller in full
-            let seller_share_msg = BankMsg::Send {
-                to_address: payment_recipient.to_string(),
-                amount: vec![coin(
-                    payment.u128(),
-                    NATIVE_DENOM.to_string(),
-                )],
-            };
-            res.messages.push(SubMsg::new(seller_share_msg));
+        if !finders_fee.is_zero() {
+            res.messages
+                .push(SubMsg::new(transfer_msg(&ask.denom, finders_fee, &finder)?));
         }
+        seller_amount -= finders_fee;
     }
 
-    Ok(())
+    if !seller_amount.is_zero() {
+        let payment_recipient = ask.funds_recipient.as_ref().unwrap_or(&ask.seller);
+        res.messages.push(SubMsg::new(transfer_msg(
+            &ask.denom,
+            seller_amount,
+            payment_recipient,
+        )?));
+    }
+
+    Ok(network_fee)
+}
+
+/// Builds the message transferring an escrowed NFT to `recipient`
+fn transfer_nft(collection: &Addr, token_id: &str, recipient: &Addr) -> StdResult<WasmMsg> {
+    let cw721_transfer_msg = Cw721ExecuteMsg::<Metadata>::TransferNft {
+        token_id: token_id.to_string(),
+        recipient: recipient.to_string(),
+    };
+
+    Ok(WasmMsg::Execute {
+        contract_addr: collection.to_string(),
+        msg: to_binary(&cw721_transfer_msg)?,
+        funds: vec![],
+    })
+}
+
+/// Builds the message sending `amount` of `denom` to `recipient`,
+/// as a bank send for native denoms or a transfer for cw20 tokens
+fn transfer_msg(
+    denom: &Denom,
+    amount: Uint128,
+    recipient: impl Into<String>,
+) -> StdResult<CosmosMsg> {
+    let recipient = recipient.into();
+    let msg = match denom {
+        Denom::Native(denom) => BankMsg::Send {
+            to_address: recipient,
+            amount: vec![coin(amount.u128(), denom)],
+        }
+        .into(),
+        Denom::Cw20(token) => WasmMsg::Execute {
+            contract_addr: token.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer { recipient, amount })?,
+            funds: vec![],
+        }
+        .into(),
+    };
+
+    Ok(msg)
 }
 
-fn price_validate(store: &dyn Storage, price: &Coin) -> Result<(), ContractError> {
-    if price.amount.is_zero() || price.denom != NATIVE_DENOM {
+/// Checks the price against the min price of its denom, which must be accepted.
+/// Returns the min price.
+fn price_validate(
+    store: &dyn Storage,
+    collection: &Addr,
+    price: Uint128,
+    denom: &Denom,
+) -> Result<Uint128, ContractError> {
+    if price.is_zero() {
         return Err(ContractError::InvalidPrice {});
     }
 
-    if price.amount < SUDO_PARAMS.load(store)?.min_price {
-        return Err(ContractError::PriceTooSmall(price.amount));
+    let min_price = match denom {
+        Denom::Native(denom) => DENOMS.may_load(store, denom)?,
+        Denom::Cw20(token) => CW20_TOKENS.may_load(store, token)?,
+    }
+    .ok_or_else(|| ContractError::InvalidDenom(denom_key(denom)))?;
+    // The min price of a collection in the denom overrides the min price of the denom
+    let min_price = COLLECTION_PARAMS
+        .may_load(store, collection)?
+        .and_then(|overrides| {
+            overrides
+                .min_prices
+                .into_iter()
+                .find(|min_price| &min_price.denom == denom)
+        })
+        .map_or(min_price, |overrides| overrides.min_price);
+    if price < min_price {
+        return Err(ContractError::PriceTooSmall(price));
+    }
+
+    Ok(min_price)
+}
+
+/// Checks that the finder is not a party to the sale
+fn finder_validate(finder: &Option<Addr>, party: &Addr) -> Result<(), ContractError> {
+    if finder.as_ref() == Some(party) {
+        return Err(ContractError::InvalidFinder(format!(
+            "{} cannot be finder",
+            party
+        )));
     }
 
     Ok(())
@@ -646,7 +2429,86 @@ fn store_bid(store: &mut dyn Storage, bid: &Bid) -> StdResult<()> {
 }
 
 fn store_ask(store: &mut dyn Storage, ask: &Ask) -> StdResult<()> {
-    asks().save(store, ask_key(&ask.collection, &ask.token_id), ask)
+    let key = ask_key(&ask.collection, &ask.token_id);
+    if !asks().has(store, key.clone()) {
+        COLLECTIONS.update(store, &ask.collection, |collection| {
+            let mut collection = collection.unwrap_or(RegisteredCollection {
+                collection: ask.collection.clone(),
+                first_listed_at: ask.created_at,
+                active_asks: 0,
+            });
+            collection.active_asks += 1;
+            StdResult::Ok(collection)
+        })?;
+    }
+    asks().save(store, key, ask)
+}
+
+/// Removes an ask, and updates the listing count of its collection
+fn remove_ask(store: &mut dyn Storage, key: AskKey) -> StdResult<()> {
+    if !asks().has(store, key.clone()) {
+        return Ok(());
+    }
+    asks().remove(store, key.clone())?;
+    COLLECTIONS.update(store, &key.0, |collection| {
+        let mut collection =
+            collection.ok_or_else(|| StdError::not_found("RegisteredCollection"))?;
+        collection.active_asks = collection.active_asks.saturating_sub(1);
+        StdResult::Ok(collection)
+    })?;
+    Ok(())
+}
+
+/// Loads the global params with the overrides of a collection applied
+pub fn load_params(store: &dyn Storage, collection: &Addr) -> StdResult<SudoParams> {
+    let params = SUDO_PARAMS.load(store)?;
+    Ok(match COLLECTION_PARAMS.may_load(store, collection)? {
+        Some(overrides) => params.with_overrides(overrides),
+        None => params,
+    })
+}
+
+/// Checks that the collection can be listed and bid on in the collection list mode
+fn collection_validate(store: &dyn Storage, collection: &Addr) -> Result<(), ContractError> {
+    let allowed = match SUDO_PARAMS.load(store)?.collection_list_mode {
+        CollectionListMode::Open => true,
+        CollectionListMode::Allowlist => ALLOWED_COLLECTIONS.has(store, collection),
+        CollectionListMode::Denylist => !DENIED_COLLECTIONS.has(store, collection),
+    };
+    if !allowed {
+        return Err(ContractError::CollectionNotAllowed(collection.to_string()));
+    }
+
+    Ok(())
+}
+
+/// Checks to enforce only the creator or admin of the collection contract can call
+fn only_collection_creator(
+    deps: Deps,
+    info: &MessageInfo,
+    collection: &Addr,
+) -> Result<(), ContractError> {
+    let query = WasmQuery::ContractInfo {
+        contract_addr: collection.to_string(),
+    };
+    let contract_info: ContractInfoResponse = deps.querier.query(&query.into())?;
+    if info.sender != contract_info.creator
+        && contract_info.admin.as_deref() != Some(info.sender.as_str())
+    {
+        return Err(ContractError::UnauthorizedCreator {});
+    }
+
+    Ok(())
+}
+
+/// Checks to enforce only operators can call
+fn only_operator(store: &dyn Storage, info: &MessageInfo) -> Result<(), ContractError> {
+    let params = SUDO_PARAMS.load(store)?;
+    if !params.operators.contains(&info.sender) {
+        return Err(ContractError::UnauthorizedOperator {});
+    }
+
+    Ok(())
 }
 
 /// Checks to enfore only NFT owner can call
@@ -726,13 +2588,14 @@ fn prepare_ask_hook(deps: Deps, ask: &Ask, action: HookAction) -> StdResult<Vec<
 
 fn prepare_sale_hook(deps: Deps, ask: &Ask, buyer: Addr) -> StdResult<Vec<SubMsg>> {
     let submsgs = SALE_HOOKS.prepare_hooks(deps.storage, |h| {
-        let msg = SaleHookMsg {
-            collection: ask.collection.to_string(),
-            token_id: ask.token_id.to_string(),
-            price: coin(ask.price.clone().u128(), NATIVE_DENOM),
-            seller: ask.seller.to_string(),
-            buyer: buyer.to_string(),
-        };
+        let msg = SaleHookMsg::new(
+            ask.collection.to_string(),
+            ask.token_id.to_string(),
+            ask.price,
+            ask.denom.clone(),
+            ask.seller.to_string(),
+            buyer.to_string(),
+        );
         let execute = WasmMsg::Execute {
             contract_addr: h.to_string(),
             msg: msg.into_binary()?,
@@ -758,8 +2621,28 @@ fn prepare_bid_hook(deps: Deps, bid: &Bid, action: HookAction) -> StdResult<Vec<
     Ok(submsgs)
 }
 
+fn prepare_collection_bid_hook(
+    deps: Deps,
+    collection_bid: &CollectionBid,
+    action: HookAction,
+) -> StdResult<Vec<SubMsg>> {
+    let submsgs = COLLECTION_BID_HOOKS.prepare_hooks(deps.storage, |h| {
+        let msg = CollectionBidHookMsg {
+            collection_bid: collection_bid.clone(),
+        };
+        let execute = WasmMsg::Execute {
+            contract_addr: h.to_string(),
+            msg: msg.into_binary(action.clone())?,
+            funds: vec![],
+        };
+        Ok(SubMsg::reply_on_error(execute, HookReply::CollectionBid as u64))
+    })?;
+
+    Ok(submsgs)
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(deps: DepsMut, _env: Env, _msg: Empty) -> Result<Response, ContractError> {
+pub fn migrate(deps: DepsMut, env: Env, msg: MigrateMsg) -> Result<Response, ContractError> {
     let current_version = cw2::get_contract_version(deps.storage)?;
     if current_version.contract != CONTRACT_NAME {
         return Err(StdError::generic_err("Cannot upgrade to a different contract").into());
@@ -779,38 +2662,168 @@ pub fn migrate(deps: DepsMut, _env: Env, _msg: Empty) -> Result<Response, Contra
     if version == new_version {
         return Ok(Response::new());
     }
+    if version < Version::new(1, 0, 0) {
+        return Err(StdError::generic_err("Cannot upgrade from a version before v1.0.0").into());
+    }
 
-    // SudoParamsV015 represents the previous state from v0.15.0 version
+    // SudoParamsV100 represents the previous state from v1.0.0 version
     #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
-    pub struct SudoParamsV015 {
-        // pub trading_fee_percent: Decimal,
+    pub struct SudoParamsV100 {
         pub ask_expiry: ExpiryRange,
         pub bid_expiry: ExpiryRange,
         pub operators: Vec<Addr>,
-        // pub max_finders_fee_percent: Decimal,
         pub min_price: Uint128,
-        pub stale_bid_duration: Duration,
-        pub bid_removal_reward_percent: Decimal,
+        pub listing_fee: Uint128,
+    }
+
+    // AskV100 represents an ask stored by v1.0.0, which was always priced in the native denom
+    #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+    pub struct AskV100 {
+        pub sale_type: SaleType,
+        pub collection: Addr,
+        pub token_id: TokenId,
+        pub img_url: String,
+        pub seller: Addr,
+        pub price: Uint128,
+        pub funds_recipient: Option<Addr>,
+        pub expires_at: Timestamp,
+        pub max_bid: Option<Uint128>,
+        pub max_bidder: Option<Addr>,
+    }
+
+    // BidV100 represents a bid stored by v1.0.0, which had no expiration
+    #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+    pub struct BidV100 {
+        pub collection: Addr,
+        pub token_id: TokenId,
+        pub bidder: Addr,
+        pub price: Uint128,
+        pub active: bool,
+        pub time: Timestamp,
     }
 
     // load state that contains the old struct type
-    let params_item: Item<SudoParamsV015> = Item::new("sudo-params");
+    let params_item: Item<SudoParamsV100> = Item::new("sudo-params");
     let current_params = params_item.load(deps.storage)?;
 
     // migrate to the new struct
+    // No trading fee is charged until governance sets it
     let new_sudo_params = SudoParams {
-        // trading_fee_percent: current_params.trading_fee_percent,
+        trading_fee_percent: Decimal::zero(),
+        fee_recipient: deps.api.addr_validate(&msg.fee_recipient)?,
         ask_expiry: current_params.ask_expiry,
         bid_expiry: current_params.bid_expiry,
         operators: current_params.operators,
-        // max_finders_fee_percent: current_params.max_finders_fee_percent,
-        min_price: current_params.min_price,
-        listing_fee: Uint128::zero(),
+        max_finders_fee_percent: Decimal::zero(),
+        auction_cancel_penalty_percent: Decimal::zero(),
+        listing_fee: coin(current_params.listing_fee.u128(), NATIVE_DENOM),
+        refund_listing_fee_on_sale: false,
+        keeper_reward: coin(0, NATIVE_DENOM),
+        extension_window: 0,
+        extension_duration: 0,
+        max_extension: 0,
+        min_bid_increment: BidIncrement::Amount(Uint128::zero()),
+        collection_list_mode: CollectionListMode::Open,
+        // Royalties are not capped until governance sets a max rate
+        max_royalty_rate: Decimal::one(),
     };
     // store migrated params
     SUDO_PARAMS.save(deps.storage, &new_sudo_params)?;
 
+    // Asks are rewritten with their denom, which also partitions the price index
+    let asks_v100: Map<AskKey, AskV100> = Map::new("asks");
+    let old_asks = asks_v100
+        .range_raw(deps.storage, None, None, StorageOrder::Ascending)
+        .map(|item| item.map(|(_, ask)| ask))
+        .collect::<StdResult<Vec<_>>>()?;
+    for namespace in ["asks", "asks__collection", "asks__collection_price", "asks__seller"] {
+        clear_namespace(deps.storage, namespace);
+    }
+    for ask in old_asks {
+        let ask = Ask {
+            sale_type: ask.sale_type,
+            collection: ask.collection,
+            token_id: ask.token_id,
+            img_url: ask.img_url,
+            seller: ask.seller,
+            price: ask.price,
+            denom: Denom::Native(NATIVE_DENOM.to_string()),
+            funds_recipient: ask.funds_recipient,
+            finders_fee_bps: None,
+            expires_at: ask.expires_at,
+            max_bid: ask.max_bid,
+            max_bidder: ask.max_bidder,
+            total_extension: 0,
+            min_bid_increment: None,
+            reserve_price: None,
+            buy_now_price: None,
+            allow_early_accept: false,
+            dutch_auction: None,
+            sealed_auction: None,
+            listing_fee: None,
+            // v1.0.0 did not record when asks were created
+            created_at: env.block.time,
+        };
+        store_ask(deps.storage, &ask)?;
+    }
+
+    // v1.0.0 kept the records of outbid and sold bids, so only the leading bid of an
+    // auction is still escrowed. It is kept until the auction ends, and the others are dropped.
+    let bids_v100: Map<BidKey, BidV100> = Map::new("bids");
+    let old_bids = bids_v100
+        .range_raw(deps.storage, None, None, StorageOrder::Ascending)
+        .map(|item| item.map(|(_, bid)| bid))
+        .collect::<StdResult<Vec<_>>>()?;
+    for namespace in [
+        "bids",
+        "bids__collection",
+        "bids__collection_token_id",
+        "bids__collection_price",
+        "bids__bidder",
+    ] {
+        clear_namespace(deps.storage, namespace);
+    }
+    for bid in old_bids {
+        let ask = asks().may_load(deps.storage, ask_key(&bid.collection, &bid.token_id))?;
+        let ask = match ask {
+            Some(ask) if ask.max_bidder == Some(bid.bidder.clone()) && ask.max_bid == Some(bid.price) => ask,
+            _ => continue,
+        };
+        let bid = Bid::new(
+            bid.collection,
+            bid.token_id,
+            bid.bidder,
+            bid.price,
+            Denom::Native(NATIVE_DENOM.to_string()),
+            bid.time,
+            ask.expires_at,
+        );
+        store_bid(deps.storage, &bid)?;
+    }
+
+    // The min price now applies per denom, starting with the previous payment denom
+    DENOMS.save(deps.storage, NATIVE_DENOM, &current_params.min_price)?;
+
     // set new contract version
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
     Ok(Response::new())
 }
+
+/// Removes every entry of a map or index, so it can be rebuilt in a new layout
+fn clear_namespace(storage: &mut dyn Storage, namespace: &str) {
+    // Keys of maps and indexes start with their namespace, prefixed by its length
+    let mut start = (namespace.len() as u16).to_be_bytes().to_vec();
+    start.extend_from_slice(namespace.as_bytes());
+    let mut end = start.clone();
+    if let Some(last) = end.last_mut() {
+        *last += 1;
+    }
+
+    let keys: Vec<_> = storage
+        .range(Some(&start), Some(&end), StorageOrder::Ascending)
+        .map(|(key, _)| key)
+        .collect();
+    for key in keys {
+        storage.remove(&key);
+    }
+}
@@ -5,6 +5,8 @@ pub mod msg;
 pub mod query;
 pub mod state;
 pub mod sudo;
+#[cfg(test)]
+mod unit_tests;
 
 pub use error::ContractError;
 pub use helpers::{ExpiryRange, ExpiryRangeError, MarketplaceContract};
@@ -1,16 +1,24 @@
 use crate::{
     helpers::ExpiryRange,
-    state::{Ask, Bid, CollectionBid, SudoParams, TokenId},
+    state::{
+        Ask, Bid, BidIncrement, CollectionBid, CollectionListMode, DenomMinPrice, DutchAuction,
+        RegisteredCollection, RoyaltyInfo, SaleType, SealedAuctionPhase, SealedAuctionPricing,
+        SealedBid, SudoParams, TokenId,
+    },
 };
-use cosmwasm_std::{to_binary, Addr, Binary, Coin, StdResult, Uint128};
+use cosmwasm_std::{coin, to_binary, Addr, Binary, Coin, Decimal, StdResult, Timestamp, Uint128};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use cw20::{Cw20Coin, Cw20ReceiveMsg, Denom};
 use cw721::Cw721ReceiveMsg;
+use cw721_base::msg::RoyaltyShare;
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct InstantiateMsg {
-    /// Fair Burn fee for winning bids
+    /// Trading fee taken from every sale
     /// 0.25% = 25, 0.5% = 50, 1% = 100, 2.5% = 250
     pub trading_fee_bps: u64,
+    /// Recipient of the trading fee
+    pub fee_recipient: String,
     /// Valid time range for Asks
     /// (min, max) in seconds
     pub ask_expiry: ExpiryRange,
@@ -23,20 +31,47 @@ pub struct InstantiateMsg {
     /// The address of the airdrop claim contract to detect sales
     pub sale_hook: Option<String>,
     /// Max basis points for the finders fee
-    // pub max_finders_fee_bps: u64,
-    /// Min value for bids and asks
-    pub min_price: Uint128,
+    pub max_finders_fee_bps: u64,
+    /// Basis points of the highest bid paid by a seller to cancel an auction with bids
+    pub auction_cancel_penalty_bps: u64,
+    /// Denoms accepted for payment, each with the min value for bids and asks
+    pub denoms: Vec<Coin>,
     /// Listing fee to reduce spam
-    pub listing_fee: Uint128,
+    pub listing_fee: Coin,
+    /// Refund the listing fee to the seller when the NFT sells
+    pub refund_listing_fee_on_sale: bool,
+    /// Reward paid out of collected listing fees for each expired ask processed by a keeper
+    pub keeper_reward: Coin,
+    /// Bids placed this many seconds before the end of an auction extend it
+    pub extension_window: u64,
+    /// Seconds an auction is extended by for each bid in the extension window
+    pub extension_duration: u64,
+    /// Max total seconds an auction can be extended by
+    pub max_extension: u64,
+    /// Min amount a bid must raise the highest bid of an auction by
+    pub min_bid_increment: BidIncrement,
+    /// Which collections can be listed and bid on
+    pub collection_list_mode: CollectionListMode,
+    /// Max royalty paid out of the seller's share of a sale
+    /// 5% = 500, 10% = 1000
+    pub max_royalty_bps: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MigrateMsg {
+    /// Recipient of the trading fee, which v1.0.0 did not charge
+    pub fee_recipient: String,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum ExecuteMsg {
+    /// Receive an NFT along with a `ReceiveNftMsg`
     ReceiveNft(Cw721ReceiveMsg),
-    /// List an NFT on the marketplace by creating a new ask
-    
-    /// Remove an existing ask from the marketplace
+    /// Receive CW20 tokens along with a `ReceiveCw20Msg`
+    Receive(Cw20ReceiveMsg),
+    /// Remove an existing ask from the marketplace. An auction with bids can be cancelled
+    /// by sending the penalty set by governance, which is paid to the highest bidder.
     RemoveAsk {
         collection: String,
         token_id: TokenId,
@@ -45,37 +80,263 @@ pub enum ExecuteMsg {
     UpdateAskPrice {
         collection: String,
         token_id: TokenId,
-        price: Coin,
+        price: Uint128,
+        denom: Denom,
     },
-    /// Place a bid on an existing ask
+    /// Place a bid on an auction, buy a fixed price ask, or make an offer on any NFT.
+    /// Offers require an expiration in seconds.
     SetBid {
         collection: String,
         token_id: TokenId,
+        expires: Option<u64>,
+        finder: Option<String>,
+    },
+    /// Place a proxy bid on an auction, escrowing the sent funds as the max price.
+    /// The contract bids on behalf of the bidder the min increment above competing bids,
+    /// up to the max price.
+    SetProxyBid {
+        collection: String,
+        token_id: TokenId,
+        finder: Option<String>,
+    },
+    /// Remove an existing bid and refund its escrowed funds
+    RemoveBid {
+        collection: String,
+        token_id: TokenId,
     },
-    /// Remove an existing bid from an ask
-    // RemoveBid {
-    //     collection: String,
-    //     token_id: TokenId,
-    // },
-    // /// Accept a bid on an existing ask
+    /// Accept the winning bid of an ended auction, or an offer on a fixed price ask.
+    /// The highest bid of an auction can be accepted early if the ask allows it.
     AcceptBid {
         collection: String,
         token_id: TokenId,
+        bidder: Option<String>,
+        finder: Option<String>,
+    },
+    /// Commit to a bid on a sealed auction with the hash of the bid, escrowing the sent
+    /// funds as a deposit. Committing again replaces the commitment and refunds the deposit.
+    CommitBid {
+        collection: String,
+        token_id: TokenId,
+        commitment: Binary,
+    },
+    /// Reveal a committed bid on a sealed auction during its reveal phase
+    RevealBid {
+        collection: String,
+        token_id: TokenId,
+        price: Uint128,
+        salt: String,
+    },
+    /// Settle an auction that has ended. Can be called by anyone.
+    SettleAuction {
+        collection: String,
+        token_id: TokenId,
+    },
+    /// Remove expired asks of a collection, returning the NFTs of fixed price asks
+    /// to their sellers and settling auctions. Can only be called by operators.
+    RemoveStaleAsks {
+        collection: String,
+        token_ids: Vec<TokenId>,
+    },
+    /// Set the royalty of a collection that doesn't report one with `GetCollectionState`.
+    /// Can only be called by the creator or admin of the collection contract.
+    SetRoyalty {
+        collection: String,
+        recipient: String,
+        royalty_rate: Decimal,
+        /// Splits the royalty between several recipients instead of paying it to `recipient`
+        shares: Option<Vec<RoyaltyShare>>,
+    },
+    /// Remove the royalty of a collection from the royalty registry.
+    /// Can only be called by the creator or admin of the collection contract.
+    RemoveRoyalty { collection: String },
+    /// Settle ended auctions, return the NFTs of other expired asks, and refund expired
+    /// bids and collection bids, up to `limit` orders. Can be called by anyone, who is
+    /// paid the keeper reward for each ask of another seller.
+    ProcessExpired { limit: Option<u32> },
+    /// Place a bid (limit order) across an entire collection
+    SetCollectionBid {
+        collection: String,
+        finders_fee_bps: Option<u64>,
+        expires: u64,
+    },
+    /// Remove a bid (limit order) across an entire collection
+    RemoveCollectionBid { collection: String },
+    /// Accept a collection bid on an NFT that is listed with a fixed price ask
+    AcceptCollectionBid {
+        collection: String,
+        token_id: TokenId,
+        bidder: String,
+        finder: Option<String>,
+    },
+}
+
+/// Messages that can be attached to an NFT sent with `SendNft`
+#[allow(clippy::large_enum_variant)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ReceiveNftMsg {
+    /// List the NFT on the marketplace by creating a new ask
+    SetAsk(AskInfo),
+    /// Sell the NFT to an existing offer on it, paying `finders_fee_bps` to the finder
+    AcceptBid {
+        bidder: String,
+        finder: Option<String>,
+        finders_fee_bps: Option<u64>,
+    },
+    /// Sell the NFT to an existing collection bid
+    AcceptCollectionBid {
+        bidder: String,
+        finder: Option<String>,
     },
 }
 
+/// Messages that can be attached to CW20 tokens sent with `Send`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ReceiveCw20Msg {
+    /// Place a bid with the sent tokens, same as `ExecuteMsg::SetBid`
+    SetBid {
+        collection: String,
+        token_id: TokenId,
+        expires: Option<u64>,
+        finder: Option<String>,
+    },
+    /// Place a proxy bid with the sent tokens, same as `ExecuteMsg::SetProxyBid`
+    SetProxyBid {
+        collection: String,
+        token_id: TokenId,
+        finder: Option<String>,
+    },
+    /// Place a collection bid with the sent tokens, same as `ExecuteMsg::SetCollectionBid`
+    SetCollectionBid {
+        collection: String,
+        finders_fee_bps: Option<u64>,
+        expires: u64,
+    },
+    /// Cancel an auction with the sent tokens as penalty, same as `ExecuteMsg::RemoveAsk`
+    RemoveAsk {
+        collection: String,
+        token_id: TokenId,
+    },
+    /// Commit to a bid on a sealed auction with the sent tokens as deposit,
+    /// same as `ExecuteMsg::CommitBid`
+    CommitBid {
+        collection: String,
+        token_id: TokenId,
+        commitment: Binary,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AskInfo {
+    pub sale_type: SaleType,
+    pub collection: Addr,
+    pub token_id: TokenId,
+    pub price: Uint128,
+    /// Native denom or CW20 token the NFT is priced in
+    pub denom: Denom,
+    pub funds_recipient: Option<Addr>,
+    /// Share of the sale paid to the finder, capped by `max_finders_fee_bps`
+    pub finders_fee_bps: Option<u64>,
+    /// Duration of the ask in seconds
+    pub expires: u64,
+    /// Overrides the min bid increment of auctions set by governance
+    pub min_bid_increment: Option<BidIncrement>,
+    /// Min winning bid of an auction, below which the NFT is returned to the seller
+    pub reserve_price: Option<Uint128>,
+    /// Keep the reserve price out of the ask returned by queries and hooks
+    pub hide_reserve_price: bool,
+    /// Price that instantly wins an auction
+    pub buy_now_price: Option<Uint128>,
+    /// Let the seller accept the highest bid of an auction before it ends
+    pub allow_early_accept: bool,
+    /// Price schedule of a Dutch auction, which starts at `price`
+    pub dutch_auction: Option<DutchAuction>,
+    /// Pricing and reveal phase of a sealed auction
+    pub sealed_auction: Option<SealedAuctionInfo>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SealedAuctionInfo {
+    pub pricing: SealedAuctionPricing,
+    /// Duration of the reveal phase in seconds, at the end of the ask
+    pub reveal_duration: u64,
+}
+
+#[allow(clippy::large_enum_variant)]
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum SudoMsg {
     /// Update the contract parameters
     /// Can only be called by governance
     UpdateParams {
+        trading_fee_bps: Option<u64>,
+        fee_recipient: Option<String>,
         ask_expiry: Option<ExpiryRange>,
         bid_expiry: Option<ExpiryRange>,
         operators: Option<Vec<String>>,
-        min_price: Option<Uint128>,
-        listing_fee: Option<Uint128>,
+        max_finders_fee_bps: Option<u64>,
+        auction_cancel_penalty_bps: Option<u64>,
+        listing_fee: Option<Coin>,
+        refund_listing_fee_on_sale: Option<bool>,
+        keeper_reward: Option<Coin>,
+        extension_window: Option<u64>,
+        extension_duration: Option<u64>,
+        max_extension: Option<u64>,
+        min_bid_increment: Option<BidIncrement>,
+        collection_list_mode: Option<CollectionListMode>,
+        max_royalty_bps: Option<u64>,
+    },
+    /// Override the global params for a collection. Params that are not set
+    /// fall back to the global params.
+    SetCollectionParams {
+        collection: String,
+        min_prices: Option<Vec<DenomMinPrice>>,
+        listing_fee: Option<Coin>,
+        ask_expiry: Option<ExpiryRange>,
+        bid_expiry: Option<ExpiryRange>,
+        sale_types: Option<Vec<SaleType>>,
+        trading_fee_bps: Option<u64>,
     },
+    /// Remove the param overrides of a collection
+    RemoveCollectionParams { collection: String },
+    /// Set the royalty of a collection that doesn't report one with `GetCollectionState`
+    SetRoyalty {
+        collection: String,
+        recipient: String,
+        royalty_rate: Decimal,
+        /// Splits the royalty between several recipients instead of paying it to `recipient`
+        shares: Option<Vec<RoyaltyShare>>,
+    },
+    /// Remove the royalty of a collection from the royalty registry
+    RemoveRoyalty { collection: String },
+    /// Accept a new payment denom, or update the min price of an accepted one
+    AddDenom { denom: String, min_price: Uint128 },
+    /// Stop accepting a payment denom
+    RemoveDenom { denom: String },
+    /// Accept a new CW20 token for payment, or update the min price of an accepted one
+    AddCw20Token { token: String, min_price: Uint128 },
+    /// Stop accepting a CW20 token for payment
+    RemoveCw20Token { token: String },
+    /// Withdraw collected listing fees in a denom, split between recipients by weight.
+    /// Withdraws everything collected in the denom when `amount` is not set.
+    WithdrawListingFees {
+        denom: String,
+        amount: Option<Uint128>,
+        recipients: Vec<ListingFeeShare>,
+    },
+    /// Allow a collection in `Allowlist` mode
+    AddAllowedCollection { collection: String },
+    /// Remove a collection from the allowlist
+    RemoveAllowedCollection { collection: String },
+    /// Deny a collection in `Denylist` mode
+    AddDeniedCollection { collection: String },
+    /// Remove a collection from the denylist
+    RemoveDeniedCollection { collection: String },
+    /// Mark a collection as verified
+    VerifyCollection { collection: String },
+    /// Remove the verified mark of a collection
+    UnverifyCollection { collection: String },
     /// Add a new operator
     AddOperator { operator: String },
     /// Remove operator
@@ -92,6 +353,17 @@ pub enum SudoMsg {
     AddSaleHook { hook: String },
     /// Remove a trade hook
     RemoveSaleHook { hook: String },
+    /// Add a new hook to be informed of all collection bids
+    AddCollectionBidHook { hook: String },
+    /// Remove a collection bid hook
+    RemoveCollectionBidHook { hook: String },
+}
+
+/// Recipient of a share of withdrawn listing fees
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ListingFeeShare {
+    pub recipient: String,
+    pub weight: u64,
 }
 
 pub type Collection = String;
@@ -162,6 +434,22 @@ impl CollectionBidOffset {
     }
 }
 
+/// Offset for pagination of the collection bids of a bidder by expiration
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CollectionBidExpiryOffset {
+    pub expires_at: Timestamp,
+    pub collection: Collection,
+}
+
+impl CollectionBidExpiryOffset {
+    pub fn new(expires_at: Timestamp, collection: Collection) -> Self {
+        CollectionBidExpiryOffset {
+            expires_at,
+            collection,
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum QueryMsg {
@@ -171,6 +459,33 @@ pub enum QueryMsg {
         start_after: Option<Collection>,
         limit: Option<u32>,
     },
+    /// Get a collection from the registry of listed collections
+    /// Return type: `RegisteredCollectionResponse`
+    RegisteredCollection { collection: Collection },
+    /// List collections in the registry of listed collections
+    /// Return type: `RegisteredCollectionsResponse`
+    RegisteredCollections {
+        start_after: Option<Collection>,
+        limit: Option<u32>,
+    },
+    /// List collections allowed by governance
+    /// Return type: `CollectionsResponse`
+    AllowedCollections {
+        start_after: Option<Collection>,
+        limit: Option<u32>,
+    },
+    /// List collections denied by governance
+    /// Return type: `CollectionsResponse`
+    DeniedCollections {
+        start_after: Option<Collection>,
+        limit: Option<u32>,
+    },
+    /// List collections verified by governance
+    /// Return type: `CollectionsResponse`
+    VerifiedCollections {
+        start_after: Option<Collection>,
+        limit: Option<u32>,
+    },
     /// Get the current ask for specific NFT
     /// Return type: `CurrentAskResponse`
     Ask {
@@ -191,17 +506,19 @@ pub enum QueryMsg {
         start_before: Option<TokenId>,
         limit: Option<u32>,
     },
-    /// Get all asks for a collection, sorted by price
+    /// Get all asks for a collection in a denom, sorted by price
     /// Return type: `AsksResponse`
     AsksSortedByPrice {
         collection: Collection,
+        denom: String,
         start_after: Option<AskOffset>,
         limit: Option<u32>,
     },
-    /// Get all asks for a collection, sorted by price in reverse
+    /// Get all asks for a collection in a denom, sorted by price in reverse
     /// Return type: `AsksResponse`
     ReverseAsksSortedByPrice {
         collection: Collection,
+        denom: String,
         start_before: Option<AskOffset>,
         limit: Option<u32>,
     },
@@ -215,6 +532,39 @@ pub enum QueryMsg {
         start_after: Option<CollectionOffset>,
         limit: Option<u32>,
     },
+    /// Get the min price of the next bid on an auction
+    /// Return type: `MinNextBidResponse`
+    MinNextBid {
+        collection: Collection,
+        token_id: TokenId,
+    },
+    /// Get the current price of a Dutch auction
+    /// Return type: `DutchAuctionPriceResponse`
+    DutchAuctionPrice {
+        collection: Collection,
+        token_id: TokenId,
+    },
+    /// Get the current phase of a sealed auction
+    /// Return type: `SealedAuctionPhaseResponse`
+    SealedAuctionPhase {
+        collection: Collection,
+        token_id: TokenId,
+    },
+    /// Get a committed bid on a sealed auction, along with its revealed price
+    /// Return type: `SealedBidResponse`
+    SealedBid {
+        collection: Collection,
+        token_id: TokenId,
+        bidder: Bidder,
+    },
+    /// Get all committed bids on a sealed auction
+    /// Return type: `SealedBidsResponse`
+    SealedBids {
+        collection: Collection,
+        token_id: TokenId,
+        start_after: Option<Bidder>,
+        limit: Option<u32>,
+    },
     /// Get data for a specific bid
     /// Return type: `BidResponse`
     Bid {
@@ -237,20 +587,58 @@ pub enum QueryMsg {
         start_after: Option<Bidder>,
         limit: Option<u32>,
     },
-    /// Get all bids for a collection, sorted by price
+    /// Get all bids for a collection in a denom, sorted by price
     /// Return type: `BidsResponse`
     BidsSortedByPrice {
         collection: Collection,
+        denom: String,
         start_after: Option<BidOffset>,
         limit: Option<u32>,
     },
-    /// Get all bids for a collection, sorted by price in reverse
+    /// Get all bids for a collection in a denom, sorted by price in reverse
     /// Return type: `BidsResponse`
     ReverseBidsSortedByPrice {
         collection: Collection,
+        denom: String,
         start_before: Option<BidOffset>,
         limit: Option<u32>,
     },
+    /// Get data for a specific collection bid
+    /// Return type: `CollectionBidResponse`
+    CollectionBid {
+        collection: Collection,
+        bidder: Bidder,
+    },
+    /// Get all collection bids by a bidder
+    /// Return type: `CollectionBidsResponse`
+    CollectionBidsByBidder {
+        bidder: Bidder,
+        start_after: Option<CollectionOffset>,
+        limit: Option<u32>,
+    },
+    /// Get all collection bids by a bidder, sorted by expiration
+    /// Return type: `CollectionBidsResponse`
+    CollectionBidsByBidderSortedByExpiration {
+        bidder: Bidder,
+        start_after: Option<CollectionBidExpiryOffset>,
+        limit: Option<u32>,
+    },
+    /// Get all collection bids for a collection in a denom, sorted by price
+    /// Return type: `CollectionBidsResponse`
+    CollectionBidsSortedByPrice {
+        collection: Collection,
+        denom: String,
+        start_after: Option<CollectionBidOffset>,
+        limit: Option<u32>,
+    },
+    /// Get all collection bids for a collection in a denom, sorted by price in reverse
+    /// Return type: `CollectionBidsResponse`
+    ReverseCollectionBidsSortedByPrice {
+        collection: Collection,
+        denom: String,
+        start_before: Option<CollectionBidOffset>,
+        limit: Option<u32>,
+    },
     /// Show all registered ask hooks
     /// Return type: `HooksResponse`
     AskHooks {},
@@ -260,9 +648,27 @@ pub enum QueryMsg {
     /// Show all registered sale hooks
     /// Return type: `HooksResponse`
     SaleHooks {},
+    /// Show all registered collection bid hooks
+    /// Return type: `HooksResponse`
+    CollectionBidHooks {},
+    /// Get the accepted payment denoms along with their min price
+    /// Return type: `DenomsResponse`
+    Denoms {},
+    /// Get the CW20 tokens accepted for payment along with their min price
+    /// Return type: `Cw20TokensResponse`
+    Cw20Tokens {},
+    /// Get the listing fees collected by the contract
+    /// Return type: `ListingFeesResponse`
+    ListingFees {},
     /// Get the config for the contract
     /// Return type: `ParamsResponse`
     Params {},
+    /// Get the params of a collection, with its overrides applied to the global params
+    /// Return type: `CollectionParamsResponse`
+    CollectionParams { collection: Collection },
+    /// Get the royalty of a collection in the royalty registry
+    /// Return type: `RoyaltyResponse`
+    Royalty { collection: Collection },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -280,11 +686,70 @@ pub struct AskCountResponse {
     pub count: u32,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MinNextBidResponse {
+    pub price: Uint128,
+    pub denom: Denom,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct DutchAuctionPriceResponse {
+    pub price: Uint128,
+    pub denom: Denom,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SealedAuctionPhaseResponse {
+    pub phase: SealedAuctionPhase,
+    pub reveal_at: Timestamp,
+    pub expires_at: Timestamp,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SealedBidResponse {
+    pub bid: Option<SealedBid>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SealedBidsResponse {
+    pub bids: Vec<SealedBid>,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct CollectionsResponse {
     pub collections: Vec<Addr>,
 }
 
+/// A registered collection, along with whether governance verified it
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CollectionResult {
+    pub collection: Addr,
+    pub first_listed_at: Timestamp,
+    pub active_asks: u64,
+    pub verified: bool,
+}
+
+impl CollectionResult {
+    pub fn new(collection: RegisteredCollection, verified: bool) -> Self {
+        CollectionResult {
+            collection: collection.collection,
+            first_listed_at: collection.first_listed_at,
+            active_asks: collection.active_asks,
+            verified,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RegisteredCollectionResponse {
+    pub collection: Option<CollectionResult>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RegisteredCollectionsResponse {
+    pub collections: Vec<CollectionResult>,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct BidResponse {
     pub bid: Option<Bid>,
@@ -300,6 +765,39 @@ pub struct ParamsResponse {
     pub params: SudoParams,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RoyaltyResponse {
+    pub royalty: Option<RoyaltyInfo>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CollectionParamsResponse {
+    pub trading_fee_percent: Decimal,
+    pub listing_fee: Coin,
+    pub ask_expiry: ExpiryRange,
+    pub bid_expiry: ExpiryRange,
+    /// Sale types that can be listed
+    pub sale_types: Vec<SaleType>,
+    /// Min prices that override the min price of their denom. The min price of
+    /// other denoms applies as returned by `Denoms` and `Cw20Tokens`.
+    pub min_prices: Vec<DenomMinPrice>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct DenomsResponse {
+    pub denoms: Vec<Coin>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Cw20TokensResponse {
+    pub tokens: Vec<Cw20Coin>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ListingFeesResponse {
+    pub fees: Vec<Coin>,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct CollectionBidResponse {
     pub bid: Option<CollectionBid>,
@@ -315,7 +813,10 @@ pub struct CollectionBidsResponse {
 pub struct SaleHookMsg {
     pub collection: String,
     pub token_id: String,
+    /// Price of the sale, whose denom is the token address for sales paid in a CW20 token
     pub price: Coin,
+    /// CW20 token the sale was paid in, `None` for sales paid in a native denom
+    pub cw20_token: Option<String>,
     pub seller: String,
     pub buyer: String,
 }
@@ -324,14 +825,20 @@ impl SaleHookMsg {
     pub fn new(
         collection: String,
         token_id: String,
-        price: Coin,
+        price: Uint128,
+        denom: Denom,
         seller: String,
         buyer: String,
     ) -> Self {
+        let (price, cw20_token) = match denom {
+            Denom::Native(denom) => (coin(price.u128(), denom), None),
+            Denom::Cw20(token) => (coin(price.u128(), token.as_str()), Some(token.to_string())),
+        };
         SaleHookMsg {
             collection,
             token_id,
             price,
+            cw20_token,
             seller,
             buyer,
         }
@@ -1,12 +1,24 @@
+use crate::execute::{load_params, min_next_bid};
 use crate::msg::{
     AskCountResponse, AskOffset, AskResponse, AsksResponse, BidOffset, BidResponse, Bidder,
-    BidsResponse, Collection, CollectionOffset, CollectionsResponse, ParamsResponse, QueryMsg,
+    BidsResponse, Collection, CollectionBidExpiryOffset, CollectionBidOffset,
+    CollectionBidResponse, CollectionBidsResponse, CollectionOffset, CollectionParamsResponse,
+    CollectionResult, CollectionsResponse, Cw20TokensResponse, DenomsResponse,
+    DutchAuctionPriceResponse, ListingFeesResponse, MinNextBidResponse, ParamsResponse, QueryMsg,
+    RegisteredCollectionResponse, RegisteredCollectionsResponse, RoyaltyResponse,
+    SealedAuctionPhaseResponse, SealedBidResponse, SealedBidsResponse,
 };
 use crate::state::{
-    ask_key, asks, bid_key, bids, BidKey, TokenId, ASK_HOOKS, BID_HOOKS, SALE_HOOKS, SUDO_PARAMS,
+    ask_key, asks, bid_key, bids, collection_bid_key, collection_bids, BidKey, CollectionBidKey,
+    SaleType, TokenId, ALLOWED_COLLECTIONS, ASK_HOOKS, BID_HOOKS, COLLECTIONS,
+    COLLECTION_BID_HOOKS, COLLECTION_PARAMS, CW20_TOKENS, DENIED_COLLECTIONS, DENOMS, LISTING_FEES,
+    ROYALTIES, SALE_HOOKS, SEALED_BIDS, SUDO_PARAMS, VERIFIED_COLLECTIONS,
 };
-use cosmwasm_std::{entry_point, to_binary, Addr, Binary, Deps, Env, Order, StdResult};
-use cw_storage_plus::{Bound, PrefixBound};
+use cosmwasm_std::{
+    coin, entry_point, to_binary, Addr, Binary, Deps, Empty, Env, Order, StdError, StdResult,
+};
+use cw20::Cw20Coin;
+use cw_storage_plus::{Bound, Map, PrefixBound};
 use cw_utils::maybe_addr;
 
 // Query limits
@@ -14,13 +26,38 @@ const DEFAULT_QUERY_LIMIT: u32 = 10;
 const MAX_QUERY_LIMIT: u32 = 100;
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     let api = deps.api;
 
     match msg {
         QueryMsg::Collections { start_after, limit } => {
             to_binary(&query_collections(deps, start_after, limit)?)
         }
+        QueryMsg::RegisteredCollection { collection } => to_binary(&query_registered_collection(
+            deps,
+            api.addr_validate(&collection)?,
+        )?),
+        QueryMsg::RegisteredCollections { start_after, limit } => {
+            to_binary(&query_registered_collections(deps, start_after, limit)?)
+        }
+        QueryMsg::AllowedCollections { start_after, limit } => to_binary(&query_collection_list(
+            deps,
+            ALLOWED_COLLECTIONS,
+            start_after,
+            limit,
+        )?),
+        QueryMsg::DeniedCollections { start_after, limit } => to_binary(&query_collection_list(
+            deps,
+            DENIED_COLLECTIONS,
+            start_after,
+            limit,
+        )?),
+        QueryMsg::VerifiedCollections { start_after, limit } => to_binary(&query_collection_list(
+            deps,
+            VERIFIED_COLLECTIONS,
+            start_after,
+            limit,
+        )?),
         QueryMsg::Ask {
             collection,
             token_id,
@@ -47,21 +84,25 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
         )?),
         QueryMsg::AsksSortedByPrice {
             collection,
+            denom,
             start_after,
             limit,
         } => to_binary(&query_asks_sorted_by_price(
             deps,
             api.addr_validate(&collection)?,
+            denom,
             start_after,
             limit,
         )?),
         QueryMsg::ReverseAsksSortedByPrice {
             collection,
+            denom,
             start_before,
             limit,
         } => to_binary(&reverse_query_asks_sorted_by_price(
             deps,
             api.addr_validate(&collection)?,
+            denom,
             start_before,
             limit,
         )?),
@@ -78,6 +119,55 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
         QueryMsg::AskCount { collection } => {
             to_binary(&query_ask_count(deps, api.addr_validate(&collection)?)?)
         }
+        QueryMsg::MinNextBid {
+            collection,
+            token_id,
+        } => to_binary(&query_min_next_bid(
+            deps,
+            env,
+            api.addr_validate(&collection)?,
+            token_id,
+        )?),
+        QueryMsg::DutchAuctionPrice {
+            collection,
+            token_id,
+        } => to_binary(&query_dutch_auction_price(
+            deps,
+            env,
+            api.addr_validate(&collection)?,
+            token_id,
+        )?),
+        QueryMsg::SealedAuctionPhase {
+            collection,
+            token_id,
+        } => to_binary(&query_sealed_auction_phase(
+            deps,
+            env,
+            api.addr_validate(&collection)?,
+            token_id,
+        )?),
+        QueryMsg::SealedBid {
+            collection,
+            token_id,
+            bidder,
+        } => to_binary(&query_sealed_bid(
+            deps,
+            api.addr_validate(&collection)?,
+            token_id,
+            api.addr_validate(&bidder)?,
+        )?),
+        QueryMsg::SealedBids {
+            collection,
+            token_id,
+            start_after,
+            limit,
+        } => to_binary(&query_sealed_bids(
+            deps,
+            api.addr_validate(&collection)?,
+            token_id,
+            start_after,
+            limit,
+        )?),
         QueryMsg::Bid {
             collection,
             token_id,
@@ -112,28 +202,92 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
         )?),
         QueryMsg::BidsSortedByPrice {
             collection,
+            denom,
             start_after,
             limit,
         } => to_binary(&query_bids_sorted_by_price(
             deps,
             api.addr_validate(&collection)?,
+            denom,
             start_after,
             limit,
         )?),
         QueryMsg::ReverseBidsSortedByPrice {
             collection,
+            denom,
             start_before,
             limit,
         } => to_binary(&reverse_query_bids_sorted_by_price(
             deps,
             api.addr_validate(&collection)?,
+            denom,
+            start_before,
+            limit,
+        )?),
+        QueryMsg::CollectionBid { collection, bidder } => to_binary(&query_collection_bid(
+            deps,
+            api.addr_validate(&collection)?,
+            api.addr_validate(&bidder)?,
+        )?),
+        QueryMsg::CollectionBidsByBidder {
+            bidder,
+            start_after,
+            limit,
+        } => to_binary(&query_collection_bids_by_bidder(
+            deps,
+            api.addr_validate(&bidder)?,
+            start_after,
+            limit,
+        )?),
+        QueryMsg::CollectionBidsByBidderSortedByExpiration {
+            bidder,
+            start_after,
+            limit,
+        } => to_binary(&query_collection_bids_by_bidder_sorted_by_expiry(
+            deps,
+            api.addr_validate(&bidder)?,
+            start_after,
+            limit,
+        )?),
+        QueryMsg::CollectionBidsSortedByPrice {
+            collection,
+            denom,
+            start_after,
+            limit,
+        } => to_binary(&query_collection_bids_sorted_by_price(
+            deps,
+            api.addr_validate(&collection)?,
+            denom,
+            start_after,
+            limit,
+        )?),
+        QueryMsg::ReverseCollectionBidsSortedByPrice {
+            collection,
+            denom,
+            start_before,
+            limit,
+        } => to_binary(&reverse_query_collection_bids_sorted_by_price(
+            deps,
+            api.addr_validate(&collection)?,
+            denom,
             start_before,
             limit,
         )?),
         QueryMsg::AskHooks {} => to_binary(&ASK_HOOKS.query_hooks(deps)?),
         QueryMsg::BidHooks {} => to_binary(&BID_HOOKS.query_hooks(deps)?),
         QueryMsg::SaleHooks {} => to_binary(&SALE_HOOKS.query_hooks(deps)?),
+        QueryMsg::CollectionBidHooks {} => to_binary(&COLLECTION_BID_HOOKS.query_hooks(deps)?),
+        QueryMsg::Denoms {} => to_binary(&query_denoms(deps)?),
+        QueryMsg::Cw20Tokens {} => to_binary(&query_cw20_tokens(deps)?),
+        QueryMsg::ListingFees {} => to_binary(&query_listing_fees(deps)?),
         QueryMsg::Params {} => to_binary(&query_params(deps)?),
+        QueryMsg::CollectionParams { collection } => to_binary(&query_collection_params(
+            deps,
+            api.addr_validate(&collection)?,
+        )?),
+        QueryMsg::Royalty { collection } => {
+            to_binary(&query_royalty(deps, api.addr_validate(&collection)?)?)
+        }
     }
 }
 
@@ -159,6 +313,68 @@ pub fn query_collections(
     Ok(CollectionsResponse { collections })
 }
 
+pub fn query_registered_collection(
+    deps: Deps,
+    collection: Addr,
+) -> StdResult<RegisteredCollectionResponse> {
+    let verified = VERIFIED_COLLECTIONS.has(deps.storage, &collection);
+    let collection = COLLECTIONS
+        .may_load(deps.storage, &collection)?
+        .map(|collection| CollectionResult::new(collection, verified));
+
+    Ok(RegisteredCollectionResponse { collection })
+}
+
+pub fn query_registered_collections(
+    deps: Deps,
+    start_after: Option<Collection>,
+    limit: Option<u32>,
+) -> StdResult<RegisteredCollectionsResponse> {
+    let limit = limit.unwrap_or(DEFAULT_QUERY_LIMIT).min(MAX_QUERY_LIMIT) as usize;
+    let start_addr = maybe_addr(deps.api, start_after)?;
+
+    let collections = COLLECTIONS
+        .range(
+            deps.storage,
+            start_addr.as_ref().map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+        .map(|item| {
+            item.map(|(collection, registered)| {
+                let verified = VERIFIED_COLLECTIONS.has(deps.storage, &collection);
+                CollectionResult::new(registered, verified)
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(RegisteredCollectionsResponse { collections })
+}
+
+/// Lists the collections of an allowlist, denylist or verified list
+pub fn query_collection_list(
+    deps: Deps,
+    list: Map<&Addr, Empty>,
+    start_after: Option<Collection>,
+    limit: Option<u32>,
+) -> StdResult<CollectionsResponse> {
+    let limit = limit.unwrap_or(DEFAULT_QUERY_LIMIT).min(MAX_QUERY_LIMIT) as usize;
+    let start_addr = maybe_addr(deps.api, start_after)?;
+
+    let collections = list
+        .keys(
+            deps.storage,
+            start_addr.as_ref().map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(CollectionsResponse { collections })
+}
+
 pub fn query_asks(
     deps: Deps,
     collection: Addr,
@@ -218,6 +434,7 @@ pub fn reverse_query_asks(
 pub fn query_asks_sorted_by_price(
     deps: Deps,
     collection: Addr,
+    denom: String,
     start_after: Option<AskOffset>,
     limit: Option<u32>,
 ) -> StdResult<AsksResponse> {
@@ -229,8 +446,8 @@ pub fn query_asks_sorted_by_price(
 
     let asks = asks()
         .idx
-        .collection_price
-        .sub_prefix(collection)
+        .collection_denom_price
+        .sub_prefix((collection, denom))
         .range(deps.storage, start, None, Order::Ascending)
         .take(limit)
         .map(|res| res.map(|item| item.1))
@@ -242,6 +459,7 @@ pub fn query_asks_sorted_by_price(
 pub fn reverse_query_asks_sorted_by_price(
     deps: Deps,
     collection: Addr,
+    denom: String,
     start_before: Option<AskOffset>,
     limit: Option<u32>,
 ) -> StdResult<AsksResponse> {
@@ -253,8 +471,8 @@ pub fn reverse_query_asks_sorted_by_price(
 
     let asks = asks()
         .idx
-        .collection_price
-        .sub_prefix(collection)
+        .collection_denom_price
+        .sub_prefix((collection, denom))
         .range(deps.storage, None, end, Order::Descending)
         .take(limit)
         .map(|res| res.map(|item| item.1))
@@ -307,6 +525,94 @@ pub fn query_ask(deps: Deps, collection: Addr, token_id: TokenId) -> StdResult<A
     Ok(AskResponse { ask })
 }
 
+pub fn query_min_next_bid(
+    deps: Deps,
+    env: Env,
+    collection: Addr,
+    token_id: TokenId,
+) -> StdResult<MinNextBidResponse> {
+    let ask = asks().load(deps.storage, ask_key(&collection, &token_id))?;
+    if ask.sale_type != SaleType::Auction {
+        return Err(StdError::generic_err("Ask is not an auction"));
+    }
+    let params = SUDO_PARAMS.load(deps.storage)?;
+
+    Ok(MinNextBidResponse {
+        price: min_next_bid(&params, &ask, &env.contract.address),
+        denom: ask.denom,
+    })
+}
+
+pub fn query_dutch_auction_price(
+    deps: Deps,
+    env: Env,
+    collection: Addr,
+    token_id: TokenId,
+) -> StdResult<DutchAuctionPriceResponse> {
+    let ask = asks().load(deps.storage, ask_key(&collection, &token_id))?;
+    let price = ask
+        .dutch_auction_price(env.block.time)
+        .ok_or_else(|| StdError::generic_err("Ask is not a Dutch auction"))?;
+
+    Ok(DutchAuctionPriceResponse {
+        price,
+        denom: ask.denom,
+    })
+}
+
+pub fn query_sealed_auction_phase(
+    deps: Deps,
+    env: Env,
+    collection: Addr,
+    token_id: TokenId,
+) -> StdResult<SealedAuctionPhaseResponse> {
+    let ask = asks().load(deps.storage, ask_key(&collection, &token_id))?;
+    let phase = ask
+        .sealed_auction_phase(env.block.time)
+        .ok_or_else(|| StdError::generic_err("Ask is not a sealed auction"))?;
+    let reveal_at = ask
+        .sealed_auction
+        .map(|sealed_auction| sealed_auction.reveal_at)
+        .unwrap_or(ask.expires_at);
+
+    Ok(SealedAuctionPhaseResponse {
+        phase,
+        reveal_at,
+        expires_at: ask.expires_at,
+    })
+}
+
+pub fn query_sealed_bid(
+    deps: Deps,
+    collection: Addr,
+    token_id: TokenId,
+    bidder: Addr,
+) -> StdResult<SealedBidResponse> {
+    let bid = SEALED_BIDS.may_load(deps.storage, (collection, token_id, bidder))?;
+
+    Ok(SealedBidResponse { bid })
+}
+
+pub fn query_sealed_bids(
+    deps: Deps,
+    collection: Addr,
+    token_id: TokenId,
+    start_after: Option<Bidder>,
+    limit: Option<u32>,
+) -> StdResult<SealedBidsResponse> {
+    let limit = limit.unwrap_or(DEFAULT_QUERY_LIMIT).min(MAX_QUERY_LIMIT) as usize;
+    let start = maybe_addr(deps.api, start_after)?.map(Bound::exclusive);
+
+    let bids = SEALED_BIDS
+        .prefix((collection, token_id))
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(_, b)| b))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(SealedBidsResponse { bids })
+}
+
 pub fn query_bid(
     deps: Deps,
     collection: Addr,
@@ -374,6 +680,7 @@ pub fn query_bids(
 pub fn query_bids_sorted_by_price(
     deps: Deps,
     collection: Addr,
+    denom: String,
     start_after: Option<BidOffset>,
     limit: Option<u32>,
 ) -> StdResult<BidsResponse> {
@@ -388,8 +695,8 @@ pub fn query_bids_sorted_by_price(
 
     let bids = bids()
         .idx
-        .collection_price
-        .sub_prefix(collection)
+        .collection_denom_price
+        .sub_prefix((collection, denom))
         .range(deps.storage, start, None, Order::Ascending)
         .take(limit)
         .map(|item| item.map(|(_, b)| b))
@@ -401,6 +708,7 @@ pub fn query_bids_sorted_by_price(
 pub fn reverse_query_bids_sorted_by_price(
     deps: Deps,
     collection: Addr,
+    denom: String,
     start_before: Option<BidOffset>,
     limit: Option<u32>,
 ) -> StdResult<BidsResponse> {
@@ -415,8 +723,8 @@ pub fn reverse_query_bids_sorted_by_price(
 
     let bids = bids()
         .idx
-        .collection_price
-        .sub_prefix(collection)
+        .collection_denom_price
+        .sub_prefix((collection, denom))
         .range(deps.storage, None, end, Order::Descending)
         .take(limit)
         .map(|item| item.map(|(_, b)| b))
@@ -425,8 +733,204 @@ pub fn reverse_query_bids_sorted_by_price(
     Ok(BidsResponse { bids })
 }
 
+pub fn query_collection_bid(
+    deps: Deps,
+    collection: Addr,
+    bidder: Addr,
+) -> StdResult<CollectionBidResponse> {
+    let bid = collection_bids().may_load(deps.storage, collection_bid_key(&collection, &bidder))?;
+
+    Ok(CollectionBidResponse { bid })
+}
+
+pub fn query_collection_bids_by_bidder(
+    deps: Deps,
+    bidder: Addr,
+    start_after: Option<CollectionOffset>,
+    limit: Option<u32>,
+) -> StdResult<CollectionBidsResponse> {
+    let limit = limit.unwrap_or(DEFAULT_QUERY_LIMIT).min(MAX_QUERY_LIMIT) as usize;
+
+    let start = if let Some(start) = start_after {
+        let collection = deps.api.addr_validate(&start.collection)?;
+        Some(Bound::exclusive(collection_bid_key(&collection, &bidder)))
+    } else {
+        None
+    };
+
+    let bids = collection_bids()
+        .idx
+        .bidder
+        .prefix(bidder)
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(_, b)| b))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(CollectionBidsResponse { bids })
+}
+
+pub fn query_collection_bids_by_bidder_sorted_by_expiry(
+    deps: Deps,
+    bidder: Addr,
+    start_after: Option<CollectionBidExpiryOffset>,
+    limit: Option<u32>,
+) -> StdResult<CollectionBidsResponse> {
+    let limit = limit.unwrap_or(DEFAULT_QUERY_LIMIT).min(MAX_QUERY_LIMIT) as usize;
+
+    let start: Option<Bound<(u64, CollectionBidKey)>> = match start_after {
+        Some(offset) => {
+            let collection = deps.api.addr_validate(&offset.collection)?;
+            Some(Bound::exclusive((
+                offset.expires_at.seconds(),
+                collection_bid_key(&collection, &bidder),
+            )))
+        }
+        None => None,
+    };
+
+    let bids = collection_bids()
+        .idx
+        .bidder_expires_at
+        .sub_prefix(bidder)
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(_, b)| b))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(CollectionBidsResponse { bids })
+}
+
+pub fn query_collection_bids_sorted_by_price(
+    deps: Deps,
+    collection: Addr,
+    denom: String,
+    start_after: Option<CollectionBidOffset>,
+    limit: Option<u32>,
+) -> StdResult<CollectionBidsResponse> {
+    let limit = limit.unwrap_or(DEFAULT_QUERY_LIMIT).min(MAX_QUERY_LIMIT) as usize;
+
+    let start: Option<Bound<(u128, CollectionBidKey)>> = match start_after {
+        Some(offset) => {
+            let bidder = deps.api.addr_validate(&offset.bidder)?;
+            Some(Bound::exclusive((
+                offset.price.u128(),
+                collection_bid_key(&collection, &bidder),
+            )))
+        }
+        None => None,
+    };
+
+    let bids = collection_bids()
+        .idx
+        .collection_denom_price
+        .sub_prefix((collection, denom))
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(_, b)| b))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(CollectionBidsResponse { bids })
+}
+
+pub fn reverse_query_collection_bids_sorted_by_price(
+    deps: Deps,
+    collection: Addr,
+    denom: String,
+    start_before: Option<CollectionBidOffset>,
+    limit: Option<u32>,
+) -> StdResult<CollectionBidsResponse> {
+    let limit = limit.unwrap_or(DEFAULT_QUERY_LIMIT).min(MAX_QUERY_LIMIT) as usize;
+
+    let end: Option<Bound<(u128, CollectionBidKey)>> = match start_before {
+        Some(offset) => {
+            let bidder = deps.api.addr_validate(&offset.bidder)?;
+            Some(Bound::exclusive((
+                offset.price.u128(),
+                collection_bid_key(&collection, &bidder),
+            )))
+        }
+        None => None,
+    };
+
+    let bids = collection_bids()
+        .idx
+        .collection_denom_price
+        .sub_prefix((collection, denom))
+        .range(deps.storage, None, end, Order::Descending)
+        .take(limit)
+        .map(|item| item.map(|(_, b)| b))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(CollectionBidsResponse { bids })
+}
+
+pub fn query_denoms(deps: Deps) -> StdResult<DenomsResponse> {
+    let denoms = DENOMS
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(denom, min_price)| coin(min_price.u128(), denom)))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(DenomsResponse { denoms })
+}
+
+pub fn query_cw20_tokens(deps: Deps) -> StdResult<Cw20TokensResponse> {
+    let tokens = CW20_TOKENS
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| {
+            item.map(|(token, min_price)| Cw20Coin {
+                address: token.to_string(),
+                amount: min_price,
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(Cw20TokensResponse { tokens })
+}
+
+pub fn query_listing_fees(deps: Deps) -> StdResult<ListingFeesResponse> {
+    let fees = LISTING_FEES
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(denom, amount)| coin(amount.u128(), denom)))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(ListingFeesResponse { fees })
+}
+
 pub fn query_params(deps: Deps) -> StdResult<ParamsResponse> {
     let config = SUDO_PARAMS.load(deps.storage)?;
 
     Ok(ParamsResponse { params: config })
 }
+
+pub fn query_collection_params(
+    deps: Deps,
+    collection: Addr,
+) -> StdResult<CollectionParamsResponse> {
+    let params = load_params(deps.storage, &collection)?;
+    let overrides = COLLECTION_PARAMS
+        .may_load(deps.storage, &collection)?
+        .unwrap_or_default();
+
+    Ok(CollectionParamsResponse {
+        trading_fee_percent: params.trading_fee_percent,
+        listing_fee: params.listing_fee,
+        ask_expiry: params.ask_expiry,
+        bid_expiry: params.bid_expiry,
+        sale_types: overrides.sale_types.unwrap_or_else(|| {
+            vec![
+                SaleType::FixedPrice,
+                SaleType::Auction,
+                SaleType::DutchAuction,
+                SaleType::SealedAuction,
+            ]
+        }),
+        min_prices: overrides.min_prices,
+    })
+}
+
+pub fn query_royalty(deps: Deps, collection: Addr) -> StdResult<RoyaltyResponse> {
+    let royalty = ROYALTIES.may_load(deps.storage, &collection)?;
+
+    Ok(RoyaltyResponse { royalty })
+}
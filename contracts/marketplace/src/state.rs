@@ -1,15 +1,19 @@
-use cosmwasm_std::{Addr, BlockInfo, Timestamp, Uint128};
-use cw_storage_plus::{Index, IndexList, IndexedMap, Item, MultiIndex};
+use cosmwasm_std::{Addr, Binary, BlockInfo, Coin, Decimal, Empty, Timestamp, Uint128};
+use cw20::Denom;
+use cw_storage_plus::{Index, IndexList, IndexedMap, Item, Map, MultiIndex};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use cw_controllers::Hooks;
+use cw721_base::state::RoyaltyShare;
 
 use crate::helpers::ExpiryRange;
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct SudoParams {
-    /// Fair Burn fee for winning bids
-    // pub trading_fee_percent: Decimal,
+    /// Trading fee taken from every sale, in percent
+    pub trading_fee_percent: Decimal,
+    /// Recipient of the trading fee
+    pub fee_recipient: Addr,
     /// Valid time range for Asks
     /// (min, max) in seconds
     pub ask_expiry: ExpiryRange,
@@ -19,16 +23,87 @@ pub struct SudoParams {
     /// Operators are entites that are responsible for maintaining the active state of Asks
     /// They listen to NFT transfer events, and update the active state of Asks
     pub operators: Vec<Addr>,
-    /// Max value for the finders fee
-    // pub max_finders_fee_percent: Decimal,
-    /// Min value for a bid
-    pub min_price: Uint128,
+    /// Max value for the finders fee, in percent
+    pub max_finders_fee_percent: Decimal,
+    /// Penalty paid by a seller to the highest bidder to cancel an auction, in percent
+    /// of the highest bid. Auctions with bids cannot be cancelled when it is zero.
+    pub auction_cancel_penalty_percent: Decimal,
     /// Listing fee to reduce spam
-    pub listing_fee: Uint128,
+    pub listing_fee: Coin,
+    /// Refund the listing fee to the seller when the NFT sells
+    pub refund_listing_fee_on_sale: bool,
+    /// Reward paid out of collected listing fees for each expired ask processed by a keeper
+    pub keeper_reward: Coin,
+    /// Bids placed this many seconds before the end of an auction extend it
+    pub extension_window: u64,
+    /// Seconds an auction is extended by for each bid in the extension window
+    pub extension_duration: u64,
+    /// Max total seconds an auction can be extended by
+    pub max_extension: u64,
+    /// Min amount a bid must raise the highest bid of an auction by
+    pub min_bid_increment: BidIncrement,
+    /// Which collections can be listed and bid on
+    pub collection_list_mode: CollectionListMode,
+    /// Max royalty rate paid out of the seller's share of a sale
+    pub max_royalty_rate: Decimal,
 }
 
 pub const SUDO_PARAMS: Item<SudoParams> = Item::new("sudo-params");
 
+/// Params of a collection set by governance, which override the global params
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+pub struct CollectionParams {
+    /// Min prices of asks and bids, which override the min price of their denom
+    pub min_prices: Vec<DenomMinPrice>,
+    pub listing_fee: Option<Coin>,
+    pub ask_expiry: Option<ExpiryRange>,
+    pub bid_expiry: Option<ExpiryRange>,
+    /// Sale types that can be listed
+    pub sale_types: Option<Vec<SaleType>>,
+    pub trading_fee_percent: Option<Decimal>,
+}
+
+/// Min price of asks and bids in a native denom or CW20 token
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct DenomMinPrice {
+    pub denom: Denom,
+    pub min_price: Uint128,
+}
+
+impl SudoParams {
+    /// Applies the overrides of a collection to the global params
+    pub fn with_overrides(self, overrides: CollectionParams) -> Self {
+        SudoParams {
+            trading_fee_percent: overrides
+                .trading_fee_percent
+                .unwrap_or(self.trading_fee_percent),
+            listing_fee: overrides.listing_fee.unwrap_or(self.listing_fee),
+            ask_expiry: overrides.ask_expiry.unwrap_or(self.ask_expiry),
+            bid_expiry: overrides.bid_expiry.unwrap_or(self.bid_expiry),
+            ..self
+        }
+    }
+}
+
+pub const COLLECTION_PARAMS: Map<&Addr, CollectionParams> = Map::new("collection-params");
+
+/// Denoms accepted for payment, mapped to the min value of bids and asks in that denom
+pub const DENOMS: Map<&str, Uint128> = Map::new("denoms");
+
+/// CW20 tokens accepted for payment, mapped to the min value of bids and asks in that token
+pub const CW20_TOKENS: Map<&Addr, Uint128> = Map::new("cw20-tokens");
+
+/// Listing fees collected by the contract, by denom
+pub const LISTING_FEES: Map<&str, Uint128> = Map::new("listing-fees");
+
+/// Key of a denom in the price indexes: the native denom, or the address of the cw20 token
+pub fn denom_key(denom: &Denom) -> String {
+    match denom {
+        Denom::Native(denom) => denom.clone(),
+        Denom::Cw20(token) => token.to_string(),
+    }
+}
+
 pub const ASK_HOOKS: Hooks = Hooks::new("ask-hooks");
 pub const BID_HOOKS: Hooks = Hooks::new("bid-hooks");
 pub const SALE_HOOKS: Hooks = Hooks::new("sale-hooks");
@@ -49,6 +124,74 @@ pub trait Order {
 pub enum SaleType {
     FixedPrice,
     Auction,
+    DutchAuction,
+    SealedAuction,
+}
+
+/// How the price of a Dutch auction falls from the ask price to the end price
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PriceDecay {
+    /// The price falls continuously
+    Linear,
+    /// The price falls at the end of every interval, in seconds
+    Stepped { interval: u64 },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct DutchAuction {
+    /// Price reached when the ask expires
+    pub end_price: Uint128,
+    pub decay: PriceDecay,
+}
+
+/// Price paid by the winner of a sealed auction
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SealedAuctionPricing {
+    /// The winner pays their own bid
+    FirstPrice,
+    /// The winner pays the second highest bid (Vickrey auction)
+    SecondPrice,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SealedAuction {
+    pub pricing: SealedAuctionPricing,
+    /// End of the commit phase and start of the reveal phase, which lasts until the ask expires
+    pub reveal_at: Timestamp,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SealedAuctionPhase {
+    /// Bidders commit the hash of their bid along with a deposit
+    Commit,
+    /// Bidders reveal the bids they committed
+    Reveal,
+    /// The auction can be settled
+    Ended,
+}
+
+/// Min raise over the highest bid of an auction
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum BidIncrement {
+    /// Absolute amount in the denom of the ask
+    Amount(Uint128),
+    /// Basis points of the highest bid
+    Bps(u64),
+}
+
+impl BidIncrement {
+    /// Increment over `bid`, which is at least 1
+    pub fn over(&self, bid: Uint128) -> Uint128 {
+        let increment = match self {
+            BidIncrement::Amount(amount) => *amount,
+            BidIncrement::Bps(bps) => bid.multiply_ratio(*bps, 10000u128),
+        };
+        increment.max(Uint128::new(1))
+    }
 }
 
 /// Represents an ask on the marketplace
@@ -60,10 +203,69 @@ pub struct Ask {
     pub img_url: String,
     pub seller: Addr,
     pub price: Uint128,
+    pub denom: Denom,
     pub funds_recipient: Option<Addr>,
+    pub finders_fee_bps: Option<u64>,
     pub expires_at: Timestamp,
     pub max_bid: Option<Uint128>,
     pub max_bidder: Option<Addr>,
+    /// Seconds the auction has been extended by late bids
+    pub total_extension: u64,
+    /// Overrides the min bid increment set by governance
+    pub min_bid_increment: Option<BidIncrement>,
+    /// Min winning bid of an auction, below which the NFT is returned to the seller
+    pub reserve_price: Option<Uint128>,
+    /// Price that instantly wins an auction
+    pub buy_now_price: Option<Uint128>,
+    /// The seller can accept the highest bid of the auction before it ends
+    pub allow_early_accept: bool,
+    /// Price schedule of a Dutch auction, which starts at `price`
+    pub dutch_auction: Option<DutchAuction>,
+    /// Phases of a sealed auction
+    pub sealed_auction: Option<SealedAuction>,
+    /// Listing fee paid by the seller, if any
+    pub listing_fee: Option<Coin>,
+    pub created_at: Timestamp,
+}
+
+impl Ask {
+    /// Current price of a Dutch auction, falling from `price` at creation
+    /// to the end price at expiration
+    pub fn dutch_auction_price(&self, now: Timestamp) -> Option<Uint128> {
+        let dutch_auction = self.dutch_auction.as_ref()?;
+
+        let duration = self
+            .expires_at
+            .seconds()
+            .saturating_sub(self.created_at.seconds());
+        let mut elapsed = now
+            .seconds()
+            .saturating_sub(self.created_at.seconds())
+            .min(duration);
+        if let PriceDecay::Stepped { interval } = dutch_auction.decay {
+            elapsed -= elapsed % interval.max(1);
+        }
+        if duration == 0 {
+            return Some(dutch_auction.end_price);
+        }
+
+        let decline = self.price.saturating_sub(dutch_auction.end_price);
+        Some(self.price - decline.multiply_ratio(elapsed, duration))
+    }
+
+    /// Current phase of a sealed auction
+    pub fn sealed_auction_phase(&self, now: Timestamp) -> Option<SealedAuctionPhase> {
+        let sealed_auction = self.sealed_auction.as_ref()?;
+
+        let phase = if now < sealed_auction.reveal_at {
+            SealedAuctionPhase::Commit
+        } else if now < self.expires_at {
+            SealedAuctionPhase::Reveal
+        } else {
+            SealedAuctionPhase::Ended
+        };
+        Some(phase)
+    }
 }
 
 impl Order for Ask {
@@ -79,16 +281,30 @@ pub fn ask_key(collection: &Addr, token_id: &TokenId) -> AskKey {
     (collection.clone(), token_id.clone())
 }
 
+/// Reserve prices that are left out of the ask, so they are not returned by ask queries and hooks
+pub const HIDDEN_RESERVES: Map<AskKey, Uint128> = Map::new("hidden-reserves");
+
+/// Max price of the leading proxy bid on an auction, which is escrowed but
+/// kept out of the ask and bid, so only the price it takes to lead is shown
+pub const PROXY_CAPS: Map<AskKey, Uint128> = Map::new("proxy-caps");
+
 /// Defines indices for accessing Asks
 pub struct AskIndicies<'a> {
     pub collection: MultiIndex<'a, Addr, Ask, AskKey>,
-    pub collection_price: MultiIndex<'a, (Addr, u128), Ask, AskKey>,
+    pub collection_denom_price: MultiIndex<'a, (Addr, String, u128), Ask, AskKey>,
     pub seller: MultiIndex<'a, Addr, Ask, AskKey>,
+    // Cannot include `Timestamp` in index, converted `Timestamp` to `seconds` and stored as `u64`
+    pub expires_at: MultiIndex<'a, u64, Ask, AskKey>,
 }
 
 impl<'a> IndexList<Ask> for AskIndicies<'a> {
     fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<Ask>> + '_> {
-        let v: Vec<&dyn Index<Ask>> = vec![&self.collection, &self.collection_price, &self.seller];
+        let v: Vec<&dyn Index<Ask>> = vec![
+            &self.collection,
+            &self.collection_denom_price,
+            &self.seller,
+            &self.expires_at,
+        ];
         Box::new(v.into_iter())
     }
 }
@@ -96,16 +312,63 @@ impl<'a> IndexList<Ask> for AskIndicies<'a> {
 pub fn asks<'a>() -> IndexedMap<'a, AskKey, Ask, AskIndicies<'a>> {
     let indexes = AskIndicies {
         collection: MultiIndex::new(|d: &Ask| d.collection.clone(), "asks", "asks__collection"),
-        collection_price: MultiIndex::new(
-            |d: &Ask| (d.collection.clone(), d.price.u128()),
+        collection_denom_price: MultiIndex::new(
+            |d: &Ask| (d.collection.clone(), denom_key(&d.denom), d.price.u128()),
             "asks",
-            "asks__collection_price",
+            "asks__collection_denom_price",
         ),
         seller: MultiIndex::new(|d: &Ask| d.seller.clone(), "asks", "asks__seller"),
+        expires_at: MultiIndex::new(|d: &Ask| d.expires_at.seconds(), "asks", "asks__expires_at"),
     };
     IndexedMap::new("asks", indexes)
 }
 
+/// A collection that has been listed on the marketplace
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RegisteredCollection {
+    pub collection: Addr,
+    pub first_listed_at: Timestamp,
+    /// Number of asks currently listed in the collection
+    pub active_asks: u64,
+}
+
+/// Collections that have been listed on the marketplace
+pub const COLLECTIONS: Map<&Addr, RegisteredCollection> = Map::new("collections");
+
+/// Royalty paid to a recipient on every sale in a collection
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RoyaltyInfo {
+    pub recipient: Addr,
+    pub royalty_rate: Decimal,
+    /// Splits the royalty between several recipients instead of paying it to `recipient`
+    #[serde(default)]
+    pub shares: Vec<RoyaltyShare>,
+}
+
+/// Royalties of collections that don't report one with `GetCollectionState`
+pub const ROYALTIES: Map<&Addr, RoyaltyInfo> = Map::new("royalties");
+
+/// Which collections can be listed and bid on
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CollectionListMode {
+    /// Any collection
+    Open,
+    /// Only allowlisted collections
+    Allowlist,
+    /// Any collection that is not denylisted
+    Denylist,
+}
+
+/// Collections allowed by governance in `Allowlist` mode
+pub const ALLOWED_COLLECTIONS: Map<&Addr, Empty> = Map::new("allowed-collections");
+
+/// Collections denied by governance in `Denylist` mode
+pub const DENIED_COLLECTIONS: Map<&Addr, Empty> = Map::new("denied-collections");
+
+/// Collections verified by governance, for front-ends to tell apart from scam collections
+pub const VERIFIED_COLLECTIONS: Map<&Addr, Empty> = Map::new("verified-collections");
+
 /// Represents a bid (offer) on the marketplace
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct Bid {
@@ -113,8 +376,10 @@ pub struct Bid {
     pub token_id: TokenId,
     pub bidder: Addr,
     pub price: Uint128,
-    pub active: bool,
+    pub denom: Denom,
     pub time: Timestamp,
+    pub expires_at: Timestamp,
+    pub finder: Option<Addr>,
 }
 
 impl Bid {
@@ -123,20 +388,29 @@ impl Bid {
         token_id: TokenId,
         bidder: Addr,
         price: Uint128,
-        active: bool,
+        denom: Denom,
         time: Timestamp,
+        expires_at: Timestamp,
     ) -> Self {
         Bid {
             collection,
             token_id,
             bidder,
             price,
-            active,
-            time
+            denom,
+            time,
+            expires_at,
+            finder: None,
         }
     }
 }
 
+impl Order for Bid {
+    fn expires_at(&self) -> Timestamp {
+        self.expires_at
+    }
+}
+
 /// Primary key for bids: (collection, token_id, bidder)
 pub type BidKey = (Addr, TokenId, Addr);
 /// Convenience bid key constructor
@@ -148,9 +422,10 @@ pub fn bid_key(collection: &Addr, token_id: &TokenId, bidder: &Addr) -> BidKey {
 pub struct BidIndicies<'a> {
     pub collection: MultiIndex<'a, Addr, Bid, BidKey>,
     pub collection_token_id: MultiIndex<'a, (Addr, TokenId), Bid, BidKey>,
-    pub collection_price: MultiIndex<'a, (Addr, u128), Bid, BidKey>,
+    pub collection_denom_price: MultiIndex<'a, (Addr, String, u128), Bid, BidKey>,
     pub bidder: MultiIndex<'a, Addr, Bid, BidKey>,
     // Cannot include `Timestamp` in index, converted `Timestamp` to `seconds` and stored as `u64`
+    pub expires_at: MultiIndex<'a, u64, Bid, BidKey>,
 }
 
 impl<'a> IndexList<Bid> for BidIndicies<'a> {
@@ -158,8 +433,9 @@ impl<'a> IndexList<Bid> for BidIndicies<'a> {
         let v: Vec<&dyn Index<Bid>> = vec![
             &self.collection,
             &self.collection_token_id,
-            &self.collection_price,
+            &self.collection_denom_price,
             &self.bidder,
+            &self.expires_at,
         ];
         Box::new(v.into_iter())
     }
@@ -173,23 +449,43 @@ pub fn bids<'a>() -> IndexedMap<'a, BidKey, Bid, BidIndicies<'a>> {
             "bids",
             "bids__collection_token_id",
         ),
-        collection_price: MultiIndex::new(
-            |d: &Bid| (d.collection.clone(), d.price.u128()),
+        collection_denom_price: MultiIndex::new(
+            |d: &Bid| (d.collection.clone(), denom_key(&d.denom), d.price.u128()),
             "bids",
-            "bids__collection_price",
+            "bids__collection_denom_price",
         ),
         bidder: MultiIndex::new(|d: &Bid| d.bidder.clone(), "bids", "bids__bidder"),
+        expires_at: MultiIndex::new(|d: &Bid| d.expires_at.seconds(), "bids", "bids__expires_at"),
     };
     IndexedMap::new("bids", indexes)
 }
 
+/// Represents a committed bid on a sealed auction. The deposit is escrowed in the contract,
+/// and must cover the bid when it is revealed.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SealedBid {
+    pub collection: Addr,
+    pub token_id: TokenId,
+    pub bidder: Addr,
+    /// SHA-256 hash of `{bidder}:{price}:{salt}`
+    pub commitment: Binary,
+    pub deposit: Uint128,
+    pub denom: Denom,
+    pub time: Timestamp,
+    pub revealed_price: Option<Uint128>,
+}
+
+/// Sealed bids, keyed like bids so the bids of an NFT can be iterated over
+pub const SEALED_BIDS: Map<BidKey, SealedBid> = Map::new("sealed-bids");
+
 /// Represents a bid (offer) across an entire collection in the marketplace
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct CollectionBid {
     pub collection: Addr,
     pub bidder: Addr,
     pub price: Uint128,
-    // pub finders_fee_bps: Option<u64>,
+    pub denom: Denom,
+    pub finders_fee_bps: Option<u64>,
     pub expires_at: Timestamp,
 }
 
@@ -209,19 +505,22 @@ pub fn collection_bid_key(collection: &Addr, bidder: &Addr) -> CollectionBidKey
 /// Defines incides for accessing collection bids
 pub struct CollectionBidIndicies<'a> {
     pub collection: MultiIndex<'a, Addr, CollectionBid, CollectionBidKey>,
-    pub collection_price: MultiIndex<'a, (Addr, u128), CollectionBid, CollectionBidKey>,
+    pub collection_denom_price:
+        MultiIndex<'a, (Addr, String, u128), CollectionBid, CollectionBidKey>,
     pub bidder: MultiIndex<'a, Addr, CollectionBid, CollectionBidKey>,
     // Cannot include `Timestamp` in index, converted `Timestamp` to `seconds` and stored as `u64`
     pub bidder_expires_at: MultiIndex<'a, (Addr, u64), CollectionBid, CollectionBidKey>,
+    pub expires_at: MultiIndex<'a, u64, CollectionBid, CollectionBidKey>,
 }
 
 impl<'a> IndexList<CollectionBid> for CollectionBidIndicies<'a> {
     fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<CollectionBid>> + '_> {
         let v: Vec<&dyn Index<CollectionBid>> = vec![
             &self.collection,
-            &self.collection_price,
+            &self.collection_denom_price,
             &self.bidder,
             &self.bidder_expires_at,
+            &self.expires_at,
         ];
         Box::new(v.into_iter())
     }
@@ -235,10 +534,10 @@ pub fn collection_bids<'a>(
             "col_bids",
             "col_bids__collection",
         ),
-        collection_price: MultiIndex::new(
-            |d: &CollectionBid| (d.collection.clone(), d.price.u128()),
+        collection_denom_price: MultiIndex::new(
+            |d: &CollectionBid| (d.collection.clone(), denom_key(&d.denom), d.price.u128()),
             "col_bids",
-            "col_bids__collection_price",
+            "col_bids__collection_denom_price",
         ),
         bidder: MultiIndex::new(
             |d: &CollectionBid| d.bidder.clone(),
@@ -250,6 +549,11 @@ pub fn collection_bids<'a>(
             "col_bids",
             "col_bids__bidder_expires_at",
         ),
+        expires_at: MultiIndex::new(
+            |d: &CollectionBid| d.expires_at.seconds(),
+            "col_bids",
+            "col_bids__expires_at",
+        ),
     };
     IndexedMap::new("col_bids", indexes)
 }
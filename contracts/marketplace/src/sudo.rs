@@ -1,15 +1,45 @@
 use crate::error::ContractError;
+use crate::execute::{store_royalty, MAX_FEE_BPS};
 use crate::helpers::ExpiryRange;
-use crate::msg::SudoMsg;
-use crate::state::{ASK_HOOKS, BID_HOOKS, SALE_HOOKS, SUDO_PARAMS};
-use cosmwasm_std::{entry_point, Addr, DepsMut, Env, Uint128, Response};
+use crate::msg::{ListingFeeShare, SudoMsg};
+use crate::state::{
+    denom_key, BidIncrement, CollectionListMode, CollectionParams, DenomMinPrice, SaleType,
+    ALLOWED_COLLECTIONS, ASK_HOOKS, BID_HOOKS, COLLECTION_BID_HOOKS, COLLECTION_PARAMS,
+    CW20_TOKENS, DENIED_COLLECTIONS, DENOMS, LISTING_FEES, ROYALTIES, SALE_HOOKS, SUDO_PARAMS,
+    VERIFIED_COLLECTIONS,
+};
+use cosmwasm_std::{
+    coin, entry_point, Addr, BankMsg, Coin, Decimal, DepsMut, Empty, Env, Response, Uint128,
+};
+use cw20::Denom;
+use cw721_base::msg::RoyaltyShare;
 
 pub struct ParamInfo {
+    trading_fee_bps: Option<u64>,
+    fee_recipient: Option<String>,
     ask_expiry: Option<ExpiryRange>,
     bid_expiry: Option<ExpiryRange>,
     operators: Option<Vec<String>>,
-    min_price: Option<Uint128>,
-    listing_fee: Option<Uint128>,
+    max_finders_fee_bps: Option<u64>,
+    auction_cancel_penalty_bps: Option<u64>,
+    listing_fee: Option<Coin>,
+    refund_listing_fee_on_sale: Option<bool>,
+    keeper_reward: Option<Coin>,
+    extension_window: Option<u64>,
+    extension_duration: Option<u64>,
+    max_extension: Option<u64>,
+    min_bid_increment: Option<BidIncrement>,
+    collection_list_mode: Option<CollectionListMode>,
+    max_royalty_bps: Option<u64>,
+}
+
+pub struct CollectionParamInfo {
+    min_prices: Option<Vec<DenomMinPrice>>,
+    listing_fee: Option<Coin>,
+    ask_expiry: Option<ExpiryRange>,
+    bid_expiry: Option<ExpiryRange>,
+    sale_types: Option<Vec<SaleType>>,
+    trading_fee_bps: Option<u64>,
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
@@ -18,23 +48,113 @@ pub fn sudo(deps: DepsMut, env: Env, msg: SudoMsg) -> Result<Response, ContractE
 
     match msg {
         SudoMsg::UpdateParams {
+            trading_fee_bps,
+            fee_recipient,
             ask_expiry,
             bid_expiry,
             operators,
-            min_price,
+            max_finders_fee_bps,
+            auction_cancel_penalty_bps,
             listing_fee,
+            refund_listing_fee_on_sale,
+            keeper_reward,
+            extension_window,
+            extension_duration,
+            max_extension,
+            min_bid_increment,
+            collection_list_mode,
+            max_royalty_bps,
         } => sudo_update_params(
             deps,
             env,
             ParamInfo {
+                trading_fee_bps,
+                fee_recipient,
                 ask_expiry,
                 bid_expiry,
                 operators,
-                // max_finders_fee_bps,
-                min_price,
+                max_finders_fee_bps,
+                auction_cancel_penalty_bps,
+                listing_fee,
+                refund_listing_fee_on_sale,
+                keeper_reward,
+                extension_window,
+                extension_duration,
+                max_extension,
+                min_bid_increment,
+                collection_list_mode,
+                max_royalty_bps,
+            },
+        ),
+        SudoMsg::SetCollectionParams {
+            collection,
+            min_prices,
+            listing_fee,
+            ask_expiry,
+            bid_expiry,
+            sale_types,
+            trading_fee_bps,
+        } => sudo_set_collection_params(
+            deps,
+            api.addr_validate(&collection)?,
+            CollectionParamInfo {
+                min_prices,
                 listing_fee,
+                ask_expiry,
+                bid_expiry,
+                sale_types,
+                trading_fee_bps,
             },
         ),
+        SudoMsg::RemoveCollectionParams { collection } => {
+            sudo_remove_collection_params(deps, api.addr_validate(&collection)?)
+        }
+        SudoMsg::SetRoyalty {
+            collection,
+            recipient,
+            royalty_rate,
+            shares,
+        } => sudo_set_royalty(
+            deps,
+            api.addr_validate(&collection)?,
+            api.addr_validate(&recipient)?,
+            royalty_rate,
+            shares.unwrap_or_default(),
+        ),
+        SudoMsg::RemoveRoyalty { collection } => {
+            sudo_remove_royalty(deps, api.addr_validate(&collection)?)
+        }
+        SudoMsg::AddDenom { denom, min_price } => sudo_add_denom(deps, denom, min_price),
+        SudoMsg::RemoveDenom { denom } => sudo_remove_denom(deps, denom),
+        SudoMsg::AddCw20Token { token, min_price } => {
+            sudo_add_cw20_token(deps, api.addr_validate(&token)?, min_price)
+        }
+        SudoMsg::RemoveCw20Token { token } => {
+            sudo_remove_cw20_token(deps, api.addr_validate(&token)?)
+        }
+        SudoMsg::WithdrawListingFees {
+            denom,
+            amount,
+            recipients,
+        } => sudo_withdraw_listing_fees(deps, denom, amount, recipients),
+        SudoMsg::AddAllowedCollection { collection } => {
+            sudo_add_allowed_collection(deps, api.addr_validate(&collection)?)
+        }
+        SudoMsg::RemoveAllowedCollection { collection } => {
+            sudo_remove_allowed_collection(deps, api.addr_validate(&collection)?)
+        }
+        SudoMsg::AddDeniedCollection { collection } => {
+            sudo_add_denied_collection(deps, api.addr_validate(&collection)?)
+        }
+        SudoMsg::RemoveDeniedCollection { collection } => {
+            sudo_remove_denied_collection(deps, api.addr_validate(&collection)?)
+        }
+        SudoMsg::VerifyCollection { collection } => {
+            sudo_verify_collection(deps, api.addr_validate(&collection)?)
+        }
+        SudoMsg::UnverifyCollection { collection } => {
+            sudo_unverify_collection(deps, api.addr_validate(&collection)?)
+        }
         SudoMsg::AddOperator { operator } => sudo_add_operator(deps, api.addr_validate(&operator)?),
         SudoMsg::RemoveOperator { operator } => {
             sudo_remove_operator(deps, api.addr_validate(&operator)?)
@@ -45,6 +165,12 @@ pub fn sudo(deps: DepsMut, env: Env, msg: SudoMsg) -> Result<Response, ContractE
         SudoMsg::RemoveSaleHook { hook } => sudo_remove_sale_hook(deps, api.addr_validate(&hook)?),
         SudoMsg::RemoveAskHook { hook } => sudo_remove_ask_hook(deps, api.addr_validate(&hook)?),
         SudoMsg::RemoveBidHook { hook } => sudo_remove_bid_hook(deps, api.addr_validate(&hook)?),
+        SudoMsg::AddCollectionBidHook { hook } => {
+            sudo_add_collection_bid_hook(deps, api.addr_validate(&hook)?)
+        }
+        SudoMsg::RemoveCollectionBidHook { hook } => {
+            sudo_remove_collection_bid_hook(deps, api.addr_validate(&hook)?)
+        }
     }
 }
 
@@ -55,43 +181,242 @@ pub fn sudo_update_params(
     param_info: ParamInfo,
 ) -> Result<Response, ContractError> {
     let ParamInfo {
+        trading_fee_bps,
+        fee_recipient,
         ask_expiry,
         bid_expiry,
         operators: _operators,
-        min_price,
+        max_finders_fee_bps,
+        auction_cancel_penalty_bps,
         listing_fee,
+        refund_listing_fee_on_sale,
+        keeper_reward,
+        extension_window,
+        extension_duration,
+        max_extension,
+        min_bid_increment,
+        collection_list_mode,
+        max_royalty_bps,
     } = param_info;
-    // if let Some(max_finders_fee_bps) = max_finders_fee_bps {
-    //     if max_finders_fee_bps > MAX_FEE_BPS {
-    //         return Err(ContractError::InvalidFindersFeeBps(max_finders_fee_bps));
-    //     }
-    // }
+    if let Some(max_finders_fee_bps) = max_finders_fee_bps {
+        if max_finders_fee_bps > MAX_FEE_BPS {
+            return Err(ContractError::InvalidFindersFeeBps(max_finders_fee_bps));
+        }
+    }
+
+    if let Some(auction_cancel_penalty_bps) = auction_cancel_penalty_bps {
+        if auction_cancel_penalty_bps > MAX_FEE_BPS {
+            return Err(ContractError::InvalidCancelPenaltyBps(
+                auction_cancel_penalty_bps,
+            ));
+        }
+    }
+
+    if let Some(trading_fee_bps) = trading_fee_bps {
+        if trading_fee_bps > MAX_FEE_BPS {
+            return Err(ContractError::InvalidTradingFeeBps(trading_fee_bps));
+        }
+    }
+
+    if let Some(max_royalty_bps) = max_royalty_bps {
+        if max_royalty_bps > MAX_FEE_BPS {
+            return Err(ContractError::InvalidMaxRoyaltyBps(max_royalty_bps));
+        }
+    }
 
     ask_expiry.as_ref().map(|a| a.validate()).transpose()?;
     bid_expiry.as_ref().map(|b| b.validate()).transpose()?;
 
     let mut params = SUDO_PARAMS.load(deps.storage)?;
 
-    // params.trading_fee_percent = trading_fee_bps
-    //     .map(Decimal::percent)
-    //     .unwrap_or(params.trading_fee_percent);
+    params.trading_fee_percent = trading_fee_bps
+        .map(Decimal::percent)
+        .unwrap_or(params.trading_fee_percent);
+
+    if let Some(fee_recipient) = fee_recipient {
+        params.fee_recipient = deps.api.addr_validate(&fee_recipient)?;
+    }
 
     params.ask_expiry = ask_expiry.unwrap_or(params.ask_expiry);
     params.bid_expiry = bid_expiry.unwrap_or(params.bid_expiry);
 
-    // params.max_finders_fee_percent = max_finders_fee_bps
-    //     .map(Decimal::percent)
-    //     .unwrap_or(params.max_finders_fee_percent);
+    params.max_finders_fee_percent = max_finders_fee_bps
+        .map(Decimal::percent)
+        .unwrap_or(params.max_finders_fee_percent);
 
-    params.min_price = min_price.unwrap_or(params.min_price);
+    params.auction_cancel_penalty_percent = auction_cancel_penalty_bps
+        .map(Decimal::percent)
+        .unwrap_or(params.auction_cancel_penalty_percent);
 
     params.listing_fee = listing_fee.unwrap_or(params.listing_fee);
+    params.refund_listing_fee_on_sale =
+        refund_listing_fee_on_sale.unwrap_or(params.refund_listing_fee_on_sale);
+    params.keeper_reward = keeper_reward.unwrap_or(params.keeper_reward);
+
+    params.extension_window = extension_window.unwrap_or(params.extension_window);
+    params.extension_duration = extension_duration.unwrap_or(params.extension_duration);
+    params.max_extension = max_extension.unwrap_or(params.max_extension);
+
+    params.min_bid_increment = min_bid_increment.unwrap_or(params.min_bid_increment);
+
+    params.collection_list_mode = collection_list_mode.unwrap_or(params.collection_list_mode);
+
+    params.max_royalty_rate = max_royalty_bps
+        .map(|bps| Decimal::from_ratio(bps, MAX_FEE_BPS))
+        .unwrap_or(params.max_royalty_rate);
 
     SUDO_PARAMS.save(deps.storage, &params)?;
 
     Ok(Response::new().add_attribute("action", "update_params"))
 }
 
+/// Withdraws collected listing fees, split between recipients by weight.
+/// Rounding dust goes to the first recipient.
+pub fn sudo_withdraw_listing_fees(
+    deps: DepsMut,
+    denom: String,
+    amount: Option<Uint128>,
+    recipients: Vec<ListingFeeShare>,
+) -> Result<Response, ContractError> {
+    let total_weight: u128 = recipients.iter().map(|share| share.weight as u128).sum();
+    if total_weight == 0 {
+        return Err(ContractError::InvalidListingFeeShares {});
+    }
+
+    let collected = LISTING_FEES
+        .may_load(deps.storage, &denom)?
+        .unwrap_or_default();
+    let amount = amount.unwrap_or(collected);
+    if amount > collected {
+        return Err(ContractError::InsufficientListingFees(collected));
+    }
+    LISTING_FEES.save(deps.storage, &denom, &(collected - amount))?;
+
+    let mut res = Response::new()
+        .add_attribute("action", "withdraw_listing_fees")
+        .add_attribute("denom", denom.clone())
+        .add_attribute("amount", amount);
+
+    let mut shares = recipients
+        .iter()
+        .map(|share| {
+            let recipient = deps.api.addr_validate(&share.recipient)?;
+            Ok((recipient, amount.multiply_ratio(share.weight, total_weight)))
+        })
+        .collect::<Result<Vec<_>, ContractError>>()?;
+    let dust = amount - shares.iter().map(|(_, share_amount)| *share_amount).sum::<Uint128>();
+    shares[0].1 += dust;
+
+    for (recipient, share_amount) in shares {
+        if !share_amount.is_zero() {
+            res = res.add_message(BankMsg::Send {
+                to_address: recipient.to_string(),
+                amount: vec![coin(share_amount.u128(), &denom)],
+            });
+        }
+    }
+
+    Ok(res)
+}
+
+/// Overrides the global params for a collection
+pub fn sudo_set_collection_params(
+    deps: DepsMut,
+    collection: Addr,
+    param_info: CollectionParamInfo,
+) -> Result<Response, ContractError> {
+    let CollectionParamInfo {
+        min_prices,
+        listing_fee,
+        ask_expiry,
+        bid_expiry,
+        sale_types,
+        trading_fee_bps,
+    } = param_info;
+
+    if let Some(trading_fee_bps) = trading_fee_bps {
+        if trading_fee_bps > MAX_FEE_BPS {
+            return Err(ContractError::InvalidTradingFeeBps(trading_fee_bps));
+        }
+    }
+
+    ask_expiry.as_ref().map(|a| a.validate()).transpose()?;
+    bid_expiry.as_ref().map(|b| b.validate()).transpose()?;
+
+    let min_prices = min_prices.unwrap_or_default();
+    for min_price in &min_prices {
+        let accepted = match &min_price.denom {
+            Denom::Native(denom) => DENOMS.has(deps.storage, denom),
+            Denom::Cw20(token) => {
+                deps.api.addr_validate(token.as_str())?;
+                CW20_TOKENS.has(deps.storage, token)
+            }
+        };
+        if !accepted {
+            return Err(ContractError::InvalidDenom(denom_key(&min_price.denom)));
+        }
+    }
+
+    let params = CollectionParams {
+        min_prices,
+        listing_fee,
+        ask_expiry,
+        bid_expiry,
+        sale_types,
+        trading_fee_percent: trading_fee_bps.map(Decimal::percent),
+    };
+    COLLECTION_PARAMS.save(deps.storage, &collection, &params)?;
+
+    let res = Response::new()
+        .add_attribute("action", "set_collection_params")
+        .add_attribute("collection", collection);
+    Ok(res)
+}
+
+pub fn sudo_remove_collection_params(
+    deps: DepsMut,
+    collection: Addr,
+) -> Result<Response, ContractError> {
+    if !COLLECTION_PARAMS.has(deps.storage, &collection) {
+        return Err(ContractError::CollectionNotFound(collection.to_string()));
+    }
+    COLLECTION_PARAMS.remove(deps.storage, &collection);
+
+    let res = Response::new()
+        .add_attribute("action", "remove_collection_params")
+        .add_attribute("collection", collection);
+    Ok(res)
+}
+
+pub fn sudo_set_royalty(
+    deps: DepsMut,
+    collection: Addr,
+    recipient: Addr,
+    royalty_rate: Decimal,
+    shares: Vec<RoyaltyShare>,
+) -> Result<Response, ContractError> {
+    store_royalty(deps, &collection, recipient.clone(), royalty_rate, shares)?;
+
+    let res = Response::new()
+        .add_attribute("action", "set_royalty")
+        .add_attribute("collection", collection)
+        .add_attribute("recipient", recipient)
+        .add_attribute("royalty_rate", royalty_rate.to_string());
+    Ok(res)
+}
+
+pub fn sudo_remove_royalty(deps: DepsMut, collection: Addr) -> Result<Response, ContractError> {
+    if !ROYALTIES.has(deps.storage, &collection) {
+        return Err(ContractError::CollectionNotFound(collection.to_string()));
+    }
+    ROYALTIES.remove(deps.storage, &collection);
+
+    let res = Response::new()
+        .add_attribute("action", "remove_royalty")
+        .add_attribute("collection", collection);
+    Ok(res)
+}
+
 pub fn sudo_add_operator(deps: DepsMut, operator: Addr) -> Result<Response, ContractError> {
     let mut params = SUDO_PARAMS.load(deps.storage)?;
     if !params.operators.iter().any(|o| o == &operator) {
@@ -120,6 +445,136 @@ pub fn sudo_remove_operator(deps: DepsMut, operator: Addr) -> Result<Response, C
     Ok(res)
 }
 
+pub fn sudo_add_denom(
+    deps: DepsMut,
+    denom: String,
+    min_price: Uint128,
+) -> Result<Response, ContractError> {
+    DENOMS.save(deps.storage, &denom, &min_price)?;
+
+    let res = Response::new()
+        .add_attribute("action", "add_denom")
+        .add_attribute("denom", denom)
+        .add_attribute("min_price", min_price);
+    Ok(res)
+}
+
+pub fn sudo_remove_denom(deps: DepsMut, denom: String) -> Result<Response, ContractError> {
+    if !DENOMS.has(deps.storage, &denom) {
+        return Err(ContractError::InvalidDenom(denom));
+    }
+    DENOMS.remove(deps.storage, &denom);
+
+    let res = Response::new()
+        .add_attribute("action", "remove_denom")
+        .add_attribute("denom", denom);
+    Ok(res)
+}
+
+pub fn sudo_add_cw20_token(
+    deps: DepsMut,
+    token: Addr,
+    min_price: Uint128,
+) -> Result<Response, ContractError> {
+    CW20_TOKENS.save(deps.storage, &token, &min_price)?;
+
+    let res = Response::new()
+        .add_attribute("action", "add_cw20_token")
+        .add_attribute("token", token)
+        .add_attribute("min_price", min_price);
+    Ok(res)
+}
+
+pub fn sudo_remove_cw20_token(deps: DepsMut, token: Addr) -> Result<Response, ContractError> {
+    if !CW20_TOKENS.has(deps.storage, &token) {
+        return Err(ContractError::InvalidDenom(token.to_string()));
+    }
+    CW20_TOKENS.remove(deps.storage, &token);
+
+    let res = Response::new()
+        .add_attribute("action", "remove_cw20_token")
+        .add_attribute("token", token);
+    Ok(res)
+}
+
+pub fn sudo_add_allowed_collection(
+    deps: DepsMut,
+    collection: Addr,
+) -> Result<Response, ContractError> {
+    ALLOWED_COLLECTIONS.save(deps.storage, &collection, &Empty {})?;
+
+    let res = Response::new()
+        .add_attribute("action", "add_allowed_collection")
+        .add_attribute("collection", collection);
+    Ok(res)
+}
+
+pub fn sudo_remove_allowed_collection(
+    deps: DepsMut,
+    collection: Addr,
+) -> Result<Response, ContractError> {
+    if !ALLOWED_COLLECTIONS.has(deps.storage, &collection) {
+        return Err(ContractError::CollectionNotFound(collection.to_string()));
+    }
+    ALLOWED_COLLECTIONS.remove(deps.storage, &collection);
+
+    let res = Response::new()
+        .add_attribute("action", "remove_allowed_collection")
+        .add_attribute("collection", collection);
+    Ok(res)
+}
+
+pub fn sudo_add_denied_collection(
+    deps: DepsMut,
+    collection: Addr,
+) -> Result<Response, ContractError> {
+    DENIED_COLLECTIONS.save(deps.storage, &collection, &Empty {})?;
+
+    let res = Response::new()
+        .add_attribute("action", "add_denied_collection")
+        .add_attribute("collection", collection);
+    Ok(res)
+}
+
+pub fn sudo_remove_denied_collection(
+    deps: DepsMut,
+    collection: Addr,
+) -> Result<Response, ContractError> {
+    if !DENIED_COLLECTIONS.has(deps.storage, &collection) {
+        return Err(ContractError::CollectionNotFound(collection.to_string()));
+    }
+    DENIED_COLLECTIONS.remove(deps.storage, &collection);
+
+    let res = Response::new()
+        .add_attribute("action", "remove_denied_collection")
+        .add_attribute("collection", collection);
+    Ok(res)
+}
+
+pub fn sudo_verify_collection(deps: DepsMut, collection: Addr) -> Result<Response, ContractError> {
+    VERIFIED_COLLECTIONS.save(deps.storage, &collection, &Empty {})?;
+
+    let res = Response::new()
+        .add_attribute("action", "verify_collection")
+        .add_attribute("collection", collection);
+    Ok(res)
+}
+
+pub fn sudo_unverify_collection(
+    deps: DepsMut,
+    collection: Addr,
+) -> Result<Response, ContractError> {
+    if !VERIFIED_COLLECTIONS.has(deps.storage, &collection) {
+        return Err(ContractError::CollectionNotFound(collection.to_string()));
+    }
+    VERIFIED_COLLECTIONS.remove(deps.storage, &collection);
+
+    let res = Response::new()
+        .add_attribute("action", "unverify_collection")
+        .add_attribute("collection", collection);
+    Ok(res)
+}
+
 pub fn sudo_add_sale_hook(deps: DepsMut, hook: Addr) -> Result<Response, ContractError> {
     SALE_HOOKS.add_hook(deps.storage, hook.clone())?;
 
@@ -147,6 +602,15 @@ pub fn sudo_add_bid_hook(deps: DepsMut, _env: Env, hook: Addr) -> Result<Respons
     Ok(res)
 }
 
+pub fn sudo_add_collection_bid_hook(deps: DepsMut, hook: Addr) -> Result<Response, ContractError> {
+    COLLECTION_BID_HOOKS.add_hook(deps.storage, hook.clone())?;
+
+    let res = Response::new()
+        .add_attribute("action", "add_collection_bid_hook")
+        .add_attribute("hook", hook);
+    Ok(res)
+}
+
 pub fn sudo_remove_sale_hook(deps: DepsMut, hook: Addr) -> Result<Response, ContractError> {
     SALE_HOOKS.remove_hook(deps.storage, hook.clone())?;
 
@@ -173,3 +637,15 @@ pub fn sudo_remove_bid_hook(deps: DepsMut, hook: Addr) -> Result<Response, Contr
         .add_attribute("hook", hook);
     Ok(res)
 }
+
+pub fn sudo_remove_collection_bid_hook(
+    deps: DepsMut,
+    hook: Addr,
+) -> Result<Response, ContractError> {
+    COLLECTION_BID_HOOKS.remove_hook(deps.storage, hook.clone())?;
+
+    let res = Response::new()
+        .add_attribute("action", "remove_collection_bid_hook")
+        .add_attribute("hook", hook);
+    Ok(res)
+}
@@ -0,0 +1,2437 @@
+use crate::error::ContractError;
+use crate::execute::{execute, instantiate, migrate, sealed_bid_commitment, MAX_SEALED_BIDS};
+use crate::helpers::ExpiryRange;
+use crate::msg::{
+    AskHookMsg, AskInfo, CollectionBidExpiryOffset, CollectionBidOffset, CollectionBidsResponse,
+    CollectionParamsResponse, DutchAuctionPriceResponse, ExecuteMsg, HookAction, InstantiateMsg,
+    ListingFeeShare, MigrateMsg, MinNextBidResponse, QueryMsg, ReceiveCw20Msg, ReceiveNftMsg,
+    RegisteredCollectionResponse, SaleHookMsg, SealedAuctionInfo, SudoMsg,
+};
+use crate::query::query;
+use crate::state::{
+    ask_key, asks, bid_key, bids, collection_bid_key, collection_bids, Ask, Bid, BidIncrement,
+    BidKey, CollectionListMode, DenomMinPrice, DutchAuction, PriceDecay, SaleType,
+    SealedAuctionPricing, DENOMS, LISTING_FEES, SEALED_BIDS, SUDO_PARAMS,
+};
+use crate::sudo::sudo;
+use cosmwasm_std::testing::{
+    mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage,
+};
+use cosmwasm_std::{
+    coin, coins, from_binary, from_slice, to_binary, Addr, BankMsg, Binary, ContractResult,
+    CosmosMsg, Decimal, DepsMut, Env, Order as StorageOrder, OwnedDeps, Response, StdResult,
+    SystemError, SystemResult, Timestamp, Uint128, WasmMsg, WasmQuery,
+};
+use cw2::{get_contract_version, set_contract_version};
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg, Denom};
+use cw721::{Cw721ReceiveMsg, NftInfoResponse};
+use cw721_base::msg::{Royalty, RoyaltyShare};
+use cw721_base::ExecuteMsg as Cw721ExecuteMsg;
+use cw721_base::QueryMsg as Cw721QueryMsg;
+use cw721_base::{CollectionInfoResponse, Metadata};
+use cw_storage_plus::{Item, Map};
+use serde::{Deserialize, Serialize};
+
+const COLLECTION: &str = "collection";
+const SELLER: &str = "seller";
+const FEE_RECIPIENT: &str = "fee_recipient";
+const ASK_HOOK: &str = "ask_hook";
+const DENOM: &str = "uheart";
+
+fn instantiate_msg() -> InstantiateMsg {
+    InstantiateMsg {
+        trading_fee_bps: 200,
+        fee_recipient: FEE_RECIPIENT.to_string(),
+        ask_expiry: ExpiryRange::new(1, 100_000),
+        bid_expiry: ExpiryRange::new(1, 100_000),
+        operators: vec![],
+        max_finders_fee_bps: 1000,
+        auction_cancel_penalty_bps: 0,
+        sale_hook: None,
+        denoms: vec![coin(1, DENOM)],
+        listing_fee: coin(10, DENOM),
+        refund_listing_fee_on_sale: false,
+        collection_list_mode: CollectionListMode::Open,
+        keeper_reward: coin(5, DENOM),
+        extension_window: 60,
+        extension_duration: 120,
+        max_extension: 300,
+        min_bid_increment: BidIncrement::Bps(1000),
+        max_royalty_bps: 2000,
+    }
+}
+
+fn setup() -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+    let mut deps = mock_dependencies();
+    mock_collection(&mut deps, None);
+
+    let msg = instantiate_msg();
+    instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+    let msg = SudoMsg::AddAskHook {
+        hook: ASK_HOOK.to_string(),
+    };
+    sudo(deps.as_mut(), mock_env(), msg).unwrap();
+
+    deps
+}
+
+/// Mocks the queries of the collection, which pays `royalty` on sales
+fn mock_collection(
+    deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>,
+    royalty: Option<Royalty>,
+) {
+    deps.querier.update_wasm(move |query| match query {
+        WasmQuery::Smart { msg, .. } => match from_binary(msg) {
+            Ok(Cw721QueryMsg::NftInfo { .. }) => {
+                let nft_info = NftInfoResponse {
+                    token_uri: None,
+                    extension: Metadata {
+                        image_url: "https://example.com/nft.png".to_string(),
+                    },
+                };
+                SystemResult::Ok(ContractResult::Ok(to_binary(&nft_info).unwrap()))
+            }
+            Ok(Cw721QueryMsg::GetCollectionState {}) => {
+                let collection_state = CollectionInfoResponse {
+                    collection_info: from_slice(b"{}").unwrap(),
+                    mint_info: None,
+                    minter: "minter".to_string(),
+                    royalty_info: royalty.clone(),
+                };
+                SystemResult::Ok(ContractResult::Ok(to_binary(&collection_state).unwrap()))
+            }
+            _ => SystemResult::Ok(ContractResult::Err("unsupported query".to_string())),
+        },
+        _ => SystemResult::Err(SystemError::UnsupportedRequest {
+            kind: "wasm".to_string(),
+        }),
+    });
+}
+
+/// Env of a block `seconds` after the mock block
+fn at(seconds: u64) -> Env {
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(seconds);
+    env
+}
+
+fn ask_info(sale_type: SaleType, token_id: &str, price: u128, expires: u64) -> AskInfo {
+    AskInfo {
+        sale_type,
+        collection: Addr::unchecked(COLLECTION),
+        token_id: token_id.to_string(),
+        price: Uint128::new(price),
+        denom: Denom::Native(DENOM.to_string()),
+        funds_recipient: None,
+        finders_fee_bps: None,
+        expires,
+        min_bid_increment: None,
+        reserve_price: None,
+        hide_reserve_price: false,
+        buy_now_price: None,
+        allow_early_accept: false,
+        dutch_auction: None,
+        sealed_auction: None,
+    }
+}
+
+/// Lists an NFT of the seller, paying the listing fee
+fn set_ask(deps: DepsMut, env: Env, ask_info: AskInfo) {
+    let rcv_msg = Cw721ReceiveMsg {
+        sender: SELLER.to_string(),
+        token_id: ask_info.token_id.clone(),
+        msg: to_binary(&ReceiveNftMsg::SetAsk(ask_info)).unwrap(),
+    };
+    let info = mock_info(COLLECTION, &coins(10, DENOM));
+    execute(deps, env, info, ExecuteMsg::ReceiveNft(rcv_msg)).unwrap();
+}
+
+fn set_bid(
+    deps: DepsMut,
+    env: Env,
+    bidder: &str,
+    token_id: &str,
+    price: u128,
+    expires: Option<u64>,
+) -> Response {
+    let msg = ExecuteMsg::SetBid {
+        collection: COLLECTION.to_string(),
+        token_id: token_id.to_string(),
+        expires,
+        finder: None,
+    };
+    execute(deps, env, mock_info(bidder, &coins(price, DENOM)), msg).unwrap()
+}
+
+fn set_collection_bid(deps: DepsMut, env: Env, bidder: &str, price: u128) -> Response {
+    let msg = ExecuteMsg::SetCollectionBid {
+        collection: COLLECTION.to_string(),
+        expires: 100,
+        finders_fee_bps: None,
+    };
+    execute(deps, env, mock_info(bidder, &coins(price, DENOM)), msg).unwrap()
+}
+
+/// Recipients and amounts of the bank transfers of a response
+fn bank_sends(res: &Response) -> Vec<(String, u128)> {
+    res.messages
+        .iter()
+        .filter_map(|sub_msg| match &sub_msg.msg {
+            CosmosMsg::Bank(BankMsg::Send { to_address, amount }) => {
+                Some((to_address.clone(), amount[0].amount.u128()))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Recipients of the NFT transfers of a response
+fn nft_transfers(res: &Response) -> Vec<String> {
+    res.messages
+        .iter()
+        .filter_map(|sub_msg| match &sub_msg.msg {
+            CosmosMsg::Wasm(WasmMsg::Execute { msg, .. }) => {
+                match from_binary::<Cw721ExecuteMsg<Metadata>>(msg) {
+                    Ok(Cw721ExecuteMsg::TransferNft { recipient, .. }) => Some(recipient),
+                    _ => None,
+                }
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Messages a response sends to the `hook` contract
+fn hook_msgs(res: &Response, hook: &str) -> Vec<Binary> {
+    res.messages
+        .iter()
+        .filter_map(|sub_msg| match &sub_msg.msg {
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr, msg, ..
+            }) if contract_addr == hook => Some(msg.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn test_collection_bid_replace_and_remove() {
+    let mut deps = setup();
+    let res = set_collection_bid(deps.as_mut(), at(0), "alice", 100);
+    assert_eq!(bank_sends(&res), vec![]);
+
+    // Replacing a collection bid refunds the previous one
+    let res = set_collection_bid(deps.as_mut(), at(10), "alice", 150);
+    assert_eq!(bank_sends(&res), vec![("alice".to_string(), 100)]);
+    let key = collection_bid_key(&Addr::unchecked(COLLECTION), &Addr::unchecked("alice"));
+    let collection_bid = collection_bids().load(&deps.storage, key.clone()).unwrap();
+    assert_eq!(collection_bid.price.u128(), 150);
+
+    let msg = ExecuteMsg::RemoveCollectionBid {
+        collection: COLLECTION.to_string(),
+    };
+    let res = execute(deps.as_mut(), at(20), mock_info("alice", &[]), msg).unwrap();
+    assert_eq!(bank_sends(&res), vec![("alice".to_string(), 150)]);
+    assert!(!collection_bids().has(&deps.storage, key));
+}
+
+#[test]
+fn test_accept_collection_bid_on_fixed_price_ask() {
+    let mut deps = setup();
+    set_ask(
+        deps.as_mut(),
+        at(0),
+        ask_info(SaleType::FixedPrice, "1", 200, 100),
+    );
+    set_collection_bid(deps.as_mut(), at(0), "alice", 120);
+
+    let key = ask_key(&Addr::unchecked(COLLECTION), &"1".to_string());
+    let ask = asks().load(&deps.storage, key.clone()).unwrap();
+
+    let msg = ExecuteMsg::AcceptCollectionBid {
+        collection: COLLECTION.to_string(),
+        token_id: "1".to_string(),
+        bidder: "alice".to_string(),
+        finder: None,
+    };
+    let err = execute(deps.as_mut(), at(10), mock_info("alice", &[]), msg.clone()).unwrap_err();
+    assert_eq!(err, ContractError::UnauthorizedOwner {});
+    let res = execute(deps.as_mut(), at(10), mock_info(SELLER, &[]), msg).unwrap();
+
+    // The NFT sells at the collection bid price, and the ask hook learns the ask is gone
+    assert_eq!(
+        bank_sends(&res),
+        vec![(FEE_RECIPIENT.to_string(), 2), (SELLER.to_string(), 118)]
+    );
+    assert_eq!(nft_transfers(&res), vec!["alice".to_string()]);
+    assert_eq!(
+        hook_msgs(&res, ASK_HOOK),
+        vec![AskHookMsg::new(ask)
+            .into_binary(HookAction::Delete)
+            .unwrap()]
+    );
+    assert!(!asks().has(&deps.storage, key));
+    let key = collection_bid_key(&Addr::unchecked(COLLECTION), &Addr::unchecked("alice"));
+    assert!(!collection_bids().has(&deps.storage, key));
+}
+
+#[test]
+fn test_accept_collection_bid_with_nft() {
+    let mut deps = setup();
+    set_collection_bid(deps.as_mut(), at(0), "alice", 120);
+
+    let rcv_msg = Cw721ReceiveMsg {
+        sender: "owner".to_string(),
+        token_id: "7".to_string(),
+        msg: to_binary(&ReceiveNftMsg::AcceptCollectionBid {
+            bidder: "alice".to_string(),
+            finder: None,
+        })
+        .unwrap(),
+    };
+    let msg = ExecuteMsg::ReceiveNft(rcv_msg);
+
+    // An expired collection bid cannot be accepted
+    let err = execute(
+        deps.as_mut(),
+        at(100),
+        mock_info(COLLECTION, &[]),
+        msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::BidExpired {});
+
+    let res = execute(deps.as_mut(), at(10), mock_info(COLLECTION, &[]), msg).unwrap();
+    assert_eq!(
+        bank_sends(&res),
+        vec![(FEE_RECIPIENT.to_string(), 2), ("owner".to_string(), 118)]
+    );
+    assert_eq!(nft_transfers(&res), vec!["alice".to_string()]);
+    assert!(hook_msgs(&res, ASK_HOOK).is_empty());
+}
+
+/// Bidders and collections of the collection bids returned by a query
+fn query_collection_bids(
+    deps: &OwnedDeps<MockStorage, MockApi, MockQuerier>,
+    msg: QueryMsg,
+) -> Vec<(String, String)> {
+    let res: CollectionBidsResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+    res.bids
+        .into_iter()
+        .map(|bid| (bid.bidder.to_string(), bid.collection.to_string()))
+        .collect()
+}
+
+#[test]
+fn test_query_collection_bids_by_price() {
+    let mut deps = setup();
+    set_collection_bid(deps.as_mut(), at(0), "alice", 100);
+    set_collection_bid(deps.as_mut(), at(0), "bob", 300);
+    set_collection_bid(deps.as_mut(), at(0), "carol", 200);
+
+    let msg = QueryMsg::CollectionBidsSortedByPrice {
+        collection: COLLECTION.to_string(),
+        denom: DENOM.to_string(),
+        start_after: Some(CollectionBidOffset::new(
+            Uint128::new(100),
+            COLLECTION.to_string(),
+            "alice".to_string(),
+        )),
+        limit: None,
+    };
+    assert_eq!(
+        query_collection_bids(&deps, msg),
+        vec![
+            ("carol".to_string(), COLLECTION.to_string()),
+            ("bob".to_string(), COLLECTION.to_string()),
+        ]
+    );
+
+    let msg = QueryMsg::ReverseCollectionBidsSortedByPrice {
+        collection: COLLECTION.to_string(),
+        denom: DENOM.to_string(),
+        start_before: None,
+        limit: Some(2),
+    };
+    assert_eq!(
+        query_collection_bids(&deps, msg),
+        vec![
+            ("bob".to_string(), COLLECTION.to_string()),
+            ("carol".to_string(), COLLECTION.to_string()),
+        ]
+    );
+}
+
+#[test]
+fn test_query_collection_bids_by_expiration() {
+    let mut deps = setup();
+    for (collection, expires) in [
+        ("collection1", 300),
+        ("collection2", 100),
+        ("collection3", 200),
+    ] {
+        let msg = ExecuteMsg::SetCollectionBid {
+            collection: collection.to_string(),
+            expires,
+            finders_fee_bps: None,
+        };
+        execute(
+            deps.as_mut(),
+            at(0),
+            mock_info("alice", &coins(100, DENOM)),
+            msg,
+        )
+        .unwrap();
+    }
+
+    // Pages start after the expiration and collection of the last bid of the previous page
+    let msg = QueryMsg::CollectionBidsByBidderSortedByExpiration {
+        bidder: "alice".to_string(),
+        start_after: Some(CollectionBidExpiryOffset::new(
+            at(100).block.time,
+            "collection2".to_string(),
+        )),
+        limit: None,
+    };
+    assert_eq!(
+        query_collection_bids(&deps, msg),
+        vec![
+            ("alice".to_string(), "collection3".to_string()),
+            ("alice".to_string(), "collection1".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn test_buy_fixed_price_ask() {
+    let mut deps = setup();
+    set_ask(
+        deps.as_mut(),
+        at(0),
+        ask_info(SaleType::FixedPrice, "1", 200, 100),
+    );
+    let key = ask_key(&Addr::unchecked(COLLECTION), &"1".to_string());
+    let ask = asks().load(&deps.storage, key.clone()).unwrap();
+
+    // Bids above the ask price are rejected rather than overpaying
+    let msg = ExecuteMsg::SetBid {
+        collection: COLLECTION.to_string(),
+        token_id: "1".to_string(),
+        expires: Some(100),
+        finder: None,
+    };
+    let err = execute(
+        deps.as_mut(),
+        at(10),
+        mock_info("alice", &coins(250, DENOM)),
+        msg,
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidPrice {});
+
+    let res = set_bid(deps.as_mut(), at(10), "alice", "1", 200, None);
+    assert_eq!(
+        bank_sends(&res),
+        vec![(FEE_RECIPIENT.to_string(), 4), (SELLER.to_string(), 196)]
+    );
+    assert_eq!(nft_transfers(&res), vec!["alice".to_string()]);
+    assert_eq!(
+        hook_msgs(&res, ASK_HOOK),
+        vec![AskHookMsg::new(ask)
+            .into_binary(HookAction::Delete)
+            .unwrap()]
+    );
+    assert!(!asks().has(&deps.storage, key));
+}
+
+#[test]
+fn test_accept_offer_on_fixed_price_ask() {
+    let mut deps = setup();
+    set_ask(
+        deps.as_mut(),
+        at(0),
+        ask_info(SaleType::FixedPrice, "1", 200, 100),
+    );
+    let key = ask_key(&Addr::unchecked(COLLECTION), &"1".to_string());
+    let ask = asks().load(&deps.storage, key.clone()).unwrap();
+
+    // A bid below the ask price is escrowed as an offer
+    let res = set_bid(deps.as_mut(), at(0), "alice", "1", 150, Some(100));
+    assert_eq!(bank_sends(&res), vec![]);
+    assert!(asks().has(&deps.storage, key.clone()));
+
+    let msg = ExecuteMsg::AcceptBid {
+        collection: COLLECTION.to_string(),
+        token_id: "1".to_string(),
+        bidder: Some("alice".to_string()),
+        finder: None,
+    };
+    let res = execute(deps.as_mut(), at(10), mock_info(SELLER, &[]), msg).unwrap();
+    assert_eq!(
+        bank_sends(&res),
+        vec![(FEE_RECIPIENT.to_string(), 3), (SELLER.to_string(), 147)]
+    );
+    assert_eq!(nft_transfers(&res), vec!["alice".to_string()]);
+    assert_eq!(
+        hook_msgs(&res, ASK_HOOK),
+        vec![AskHookMsg::new(ask)
+            .into_binary(HookAction::Delete)
+            .unwrap()]
+    );
+    assert!(!asks().has(&deps.storage, key));
+    let key = bid_key(
+        &Addr::unchecked(COLLECTION),
+        &"1".to_string(),
+        &Addr::unchecked("alice"),
+    );
+    assert!(!bids().has(&deps.storage, key));
+}
+
+#[test]
+fn test_accept_offer_with_nft() {
+    let mut deps = setup();
+    set_bid(deps.as_mut(), at(0), "alice", "7", 80, Some(100));
+
+    // Offers on unlisted NFTs need an expiration
+    let msg = ExecuteMsg::SetBid {
+        collection: COLLECTION.to_string(),
+        token_id: "7".to_string(),
+        expires: None,
+        finder: None,
+    };
+    let err = execute(
+        deps.as_mut(),
+        at(0),
+        mock_info("bob", &coins(90, DENOM)),
+        msg,
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidDuration {});
+
+    // Raising an offer refunds the previous one
+    let res = set_bid(deps.as_mut(), at(10), "alice", "7", 90, Some(100));
+    assert_eq!(bank_sends(&res), vec![("alice".to_string(), 80)]);
+
+    let rcv_msg = Cw721ReceiveMsg {
+        sender: "owner".to_string(),
+        token_id: "7".to_string(),
+        msg: to_binary(&ReceiveNftMsg::AcceptBid {
+            bidder: "alice".to_string(),
+            finder: None,
+            finders_fee_bps: None,
+        })
+        .unwrap(),
+    };
+    let msg = ExecuteMsg::ReceiveNft(rcv_msg);
+    let err = execute(
+        deps.as_mut(),
+        at(110),
+        mock_info(COLLECTION, &[]),
+        msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::BidExpired {});
+
+    let res = execute(deps.as_mut(), at(20), mock_info(COLLECTION, &[]), msg).unwrap();
+    assert_eq!(
+        bank_sends(&res),
+        vec![(FEE_RECIPIENT.to_string(), 1), ("owner".to_string(), 89)]
+    );
+    assert_eq!(nft_transfers(&res), vec!["alice".to_string()]);
+}
+
+fn remove_bid(
+    deps: DepsMut,
+    env: Env,
+    bidder: &str,
+    token_id: &str,
+) -> Result<Response, ContractError> {
+    let msg = ExecuteMsg::RemoveBid {
+        collection: COLLECTION.to_string(),
+        token_id: token_id.to_string(),
+    };
+    execute(deps, env, mock_info(bidder, &[]), msg)
+}
+
+#[test]
+fn test_remove_offer() {
+    let mut deps = setup();
+    set_bid(deps.as_mut(), at(0), "alice", "7", 80, Some(100));
+
+    let res = remove_bid(deps.as_mut(), at(10), "alice", "7").unwrap();
+    assert_eq!(bank_sends(&res), vec![("alice".to_string(), 80)]);
+    let key = bid_key(
+        &Addr::unchecked(COLLECTION),
+        &"7".to_string(),
+        &Addr::unchecked("alice"),
+    );
+    assert!(!bids().has(&deps.storage, key));
+
+    // The bid is gone, so it cannot be refunded twice
+    remove_bid(deps.as_mut(), at(20), "alice", "7").unwrap_err();
+}
+
+#[test]
+fn test_remove_auction_bid() {
+    let mut deps = setup();
+    set_ask(
+        deps.as_mut(),
+        at(0),
+        ask_info(SaleType::Auction, "1", 100, 1000),
+    );
+    set_bid(deps.as_mut(), at(10), "alice", "1", 100, None);
+
+    // The highest bidder cannot withdraw
+    let err = remove_bid(deps.as_mut(), at(20), "alice", "1").unwrap_err();
+    assert_eq!(err, ContractError::MaxBidderNotRemove {});
+
+    // An outbid bidder is refunded right away, and has no bid left to remove
+    let res = set_bid(deps.as_mut(), at(30), "bob", "1", 150, None);
+    assert_eq!(bank_sends(&res), vec![("alice".to_string(), 100)]);
+    remove_bid(deps.as_mut(), at(40), "alice", "1").unwrap_err();
+}
+
+#[test]
+fn test_trading_fee_before_royalty() {
+    let mut deps = setup();
+    let royalty = Royalty {
+        address: "creator".to_string(),
+        royalty_rate: Decimal::percent(10),
+        shares: vec![],
+    };
+    mock_collection(&mut deps, Some(royalty));
+    set_ask(
+        deps.as_mut(),
+        at(0),
+        ask_info(SaleType::FixedPrice, "1", 1000, 100),
+    );
+
+    // The trading fee is taken first, and the royalty is paid out of the rest
+    let res = set_bid(deps.as_mut(), at(10), "buyer", "1", 1000, None);
+    assert_eq!(
+        bank_sends(&res),
+        vec![
+            (FEE_RECIPIENT.to_string(), 20),
+            ("creator".to_string(), 98),
+            (SELLER.to_string(), 882),
+        ]
+    );
+}
+
+#[test]
+fn test_update_trading_fee() {
+    let mut deps = setup();
+    let update_params = |trading_fee_bps| SudoMsg::UpdateParams {
+        trading_fee_bps: Some(trading_fee_bps),
+        fee_recipient: Some("treasury".to_string()),
+        ask_expiry: None,
+        bid_expiry: None,
+        operators: None,
+        max_finders_fee_bps: None,
+        auction_cancel_penalty_bps: None,
+        listing_fee: None,
+        refund_listing_fee_on_sale: None,
+        collection_list_mode: None,
+        max_royalty_bps: None,
+        keeper_reward: None,
+        extension_window: None,
+        extension_duration: None,
+        max_extension: None,
+        min_bid_increment: None,
+    };
+    let err = sudo(deps.as_mut(), mock_env(), update_params(10001)).unwrap_err();
+    assert_eq!(err, ContractError::InvalidTradingFeeBps(10001));
+    sudo(deps.as_mut(), mock_env(), update_params(500)).unwrap();
+
+    set_ask(
+        deps.as_mut(),
+        at(0),
+        ask_info(SaleType::FixedPrice, "1", 1000, 100),
+    );
+    let res = set_bid(deps.as_mut(), at(10), "buyer", "1", 1000, None);
+    assert_eq!(
+        bank_sends(&res),
+        vec![("treasury".to_string(), 50), (SELLER.to_string(), 950)]
+    );
+}
+
+#[test]
+fn test_migrate_from_v1() {
+    #[derive(Serialize, Deserialize)]
+    struct SudoParamsV100 {
+        ask_expiry: ExpiryRange,
+        bid_expiry: ExpiryRange,
+        operators: Vec<Addr>,
+        min_price: Uint128,
+        listing_fee: Uint128,
+    }
+
+    #[derive(Serialize, Deserialize)]
+    struct AskV100 {
+        sale_type: SaleType,
+        collection: Addr,
+        token_id: String,
+        img_url: String,
+        seller: Addr,
+        price: Uint128,
+        funds_recipient: Option<Addr>,
+        expires_at: Timestamp,
+        max_bid: Option<Uint128>,
+        max_bidder: Option<Addr>,
+    }
+
+    #[derive(Serialize, Deserialize)]
+    struct BidV100 {
+        collection: Addr,
+        token_id: String,
+        bidder: Addr,
+        price: Uint128,
+        active: bool,
+        time: Timestamp,
+    }
+
+    let mut deps = setup();
+    set_ask(
+        deps.as_mut(),
+        at(0),
+        ask_info(SaleType::Auction, "1", 100, 1000),
+    );
+    let key = ask_key(&Addr::unchecked(COLLECTION), &"1".to_string());
+    let mut ask = asks().load(&deps.storage, key.clone()).unwrap();
+    ask.max_bidder = Some(Addr::unchecked("alice"));
+    ask.max_bid = Some(Uint128::new(150));
+    asks().save(&mut deps.storage, key.clone(), &ask).unwrap();
+    let ask_v100 = AskV100 {
+        sale_type: ask.sale_type.clone(),
+        collection: ask.collection.clone(),
+        token_id: ask.token_id.clone(),
+        img_url: ask.img_url.clone(),
+        seller: ask.seller.clone(),
+        price: ask.price,
+        funds_recipient: None,
+        expires_at: ask.expires_at,
+        max_bid: ask.max_bid,
+        max_bidder: ask.max_bidder.clone(),
+    };
+    Map::new("asks")
+        .save(&mut deps.storage, key.clone(), &ask_v100)
+        .unwrap();
+
+    // Bob was outbid by Alice, and Carol won an auction that was settled
+    let bids_v100: Map<BidKey, BidV100> = Map::new("bids");
+    for (token_id, bidder, price, active) in [
+        ("1", "alice", 150, true),
+        ("1", "bob", 120, false),
+        ("2", "carol", 300, true),
+    ] {
+        let bid = BidV100 {
+            collection: Addr::unchecked(COLLECTION),
+            token_id: token_id.to_string(),
+            bidder: Addr::unchecked(bidder),
+            price: Uint128::new(price),
+            active,
+            time: mock_env().block.time,
+        };
+        let key = bid_key(&bid.collection, &bid.token_id, &bid.bidder);
+        // The indexes of v1.0.0 are the ones of the current bids
+        let indexed_bid = Bid::new(
+            bid.collection.clone(),
+            bid.token_id.clone(),
+            bid.bidder.clone(),
+            bid.price,
+            Denom::Native(DENOM.to_string()),
+            bid.time,
+            bid.time,
+        );
+        bids()
+            .save(&mut deps.storage, key.clone(), &indexed_bid)
+            .unwrap();
+        bids_v100.save(&mut deps.storage, key, &bid).unwrap();
+    }
+
+    set_contract_version(&mut deps.storage, "crates.io:human-marketplace", "1.0.0").unwrap();
+    let params = SudoParamsV100 {
+        ask_expiry: ExpiryRange::new(1, 100_000),
+        bid_expiry: ExpiryRange::new(1, 100_000),
+        operators: vec![],
+        min_price: Uint128::new(1),
+        listing_fee: Uint128::new(10),
+    };
+    Item::new("sudo-params")
+        .save(&mut deps.storage, &params)
+        .unwrap();
+
+    let msg = MigrateMsg {
+        fee_recipient: FEE_RECIPIENT.to_string(),
+    };
+    migrate(deps.as_mut(), mock_env(), msg).unwrap();
+
+    let version = get_contract_version(&deps.storage).unwrap();
+    assert_eq!(version.version, env!("CARGO_PKG_VERSION"));
+    let params = SUDO_PARAMS.load(&deps.storage).unwrap();
+    assert_eq!(params.fee_recipient, Addr::unchecked(FEE_RECIPIENT));
+    assert_eq!(params.trading_fee_percent, Decimal::zero());
+    assert_eq!(params.listing_fee, coin(10, DENOM));
+    let denoms = DENOMS
+        .range(&deps.storage, None, None, StorageOrder::Ascending)
+        .collect::<StdResult<Vec<_>>>()
+        .unwrap();
+    assert_eq!(denoms, vec![(DENOM.to_string(), Uint128::new(1))]);
+
+    // Asks were priced in the native denom, and did not record their listing fee
+    let migrated_ask = asks().load(&deps.storage, key).unwrap();
+    assert_eq!(
+        migrated_ask,
+        Ask {
+            listing_fee: None,
+            ..ask
+        }
+    );
+
+    // Only the escrowed bid is kept, and it expires with its auction
+    let migrated_bids = bids()
+        .range(&deps.storage, None, None, StorageOrder::Ascending)
+        .map(|item| item.unwrap().1)
+        .collect::<Vec<_>>();
+    assert_eq!(migrated_bids.len(), 1);
+    assert_eq!(migrated_bids[0].bidder, Addr::unchecked("alice"));
+    assert_eq!(migrated_bids[0].expires_at, ask.expires_at);
+    assert_eq!(migrated_bids[0].denom, Denom::Native(DENOM.to_string()));
+    let bob_bids = bids()
+        .idx
+        .bidder
+        .prefix(Addr::unchecked("bob"))
+        .range_raw(&deps.storage, None, None, StorageOrder::Ascending)
+        .count();
+    assert_eq!(bob_bids, 0);
+}
+
+#[test]
+fn test_finders_fee_on_fixed_price_ask() {
+    let mut deps = setup();
+    let mut info = ask_info(SaleType::FixedPrice, "1", 1000, 100);
+    info.finders_fee_bps = Some(2000);
+    let rcv_msg = Cw721ReceiveMsg {
+        sender: SELLER.to_string(),
+        token_id: "1".to_string(),
+        msg: to_binary(&ReceiveNftMsg::SetAsk(info.clone())).unwrap(),
+    };
+    let err = execute(
+        deps.as_mut(),
+        at(0),
+        mock_info(COLLECTION, &coins(10, DENOM)),
+        ExecuteMsg::ReceiveNft(rcv_msg),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidFindersFeeBps(2000));
+
+    info.finders_fee_bps = Some(500);
+    set_ask(deps.as_mut(), at(0), info);
+
+    let set_bid_msg = |finder: &str| ExecuteMsg::SetBid {
+        collection: COLLECTION.to_string(),
+        token_id: "1".to_string(),
+        expires: None,
+        finder: Some(finder.to_string()),
+    };
+    let err = execute(
+        deps.as_mut(),
+        at(10),
+        mock_info("alice", &coins(1000, DENOM)),
+        set_bid_msg("alice"),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidFinder("alice cannot be finder".to_string())
+    );
+
+    // The finders fee comes out of the seller's share
+    let res = execute(
+        deps.as_mut(),
+        at(10),
+        mock_info("alice", &coins(1000, DENOM)),
+        set_bid_msg("finder"),
+    )
+    .unwrap();
+    assert_eq!(
+        bank_sends(&res),
+        vec![
+            (FEE_RECIPIENT.to_string(), 20),
+            ("finder".to_string(), 50),
+            (SELLER.to_string(), 930),
+        ]
+    );
+}
+
+#[test]
+fn test_finders_fee_on_unlisted_offer() {
+    let mut deps = setup();
+    set_bid(deps.as_mut(), at(0), "alice", "7", 1000, Some(100));
+
+    let accept_bid_msg = |finder: &str, finders_fee_bps| {
+        let rcv_msg = Cw721ReceiveMsg {
+            sender: "owner".to_string(),
+            token_id: "7".to_string(),
+            msg: to_binary(&ReceiveNftMsg::AcceptBid {
+                bidder: "alice".to_string(),
+                finder: Some(finder.to_string()),
+                finders_fee_bps: Some(finders_fee_bps),
+            })
+            .unwrap(),
+        };
+        ExecuteMsg::ReceiveNft(rcv_msg)
+    };
+    let info = mock_info(COLLECTION, &[]);
+    let err = execute(
+        deps.as_mut(),
+        at(10),
+        info.clone(),
+        accept_bid_msg("owner", 500),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidFinder("owner cannot be finder".to_string())
+    );
+    let err = execute(
+        deps.as_mut(),
+        at(10),
+        info.clone(),
+        accept_bid_msg("finder", 2000),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidFindersFeeBps(2000));
+
+    // Without a listing, the owner sets the finders fee when accepting
+    let res = execute(deps.as_mut(), at(10), info, accept_bid_msg("finder", 500)).unwrap();
+    assert_eq!(
+        bank_sends(&res),
+        vec![
+            (FEE_RECIPIENT.to_string(), 20),
+            ("finder".to_string(), 50),
+            ("owner".to_string(), 930),
+        ]
+    );
+    assert_eq!(nft_transfers(&res), vec!["alice".to_string()]);
+}
+
+#[test]
+fn test_finders_fee_on_collection_bid() {
+    let mut deps = setup();
+    let msg = ExecuteMsg::SetCollectionBid {
+        collection: COLLECTION.to_string(),
+        expires: 100,
+        finders_fee_bps: Some(2000),
+    };
+    let info = mock_info("alice", &coins(1000, DENOM));
+    let err = execute(deps.as_mut(), at(0), info.clone(), msg).unwrap_err();
+    assert_eq!(err, ContractError::InvalidFindersFeeBps(2000));
+    let msg = ExecuteMsg::SetCollectionBid {
+        collection: COLLECTION.to_string(),
+        expires: 100,
+        finders_fee_bps: Some(500),
+    };
+    execute(deps.as_mut(), at(0), info, msg).unwrap();
+
+    // The bidder offers the finders fee, paid to the finder the owner names
+    let rcv_msg = Cw721ReceiveMsg {
+        sender: "owner".to_string(),
+        token_id: "7".to_string(),
+        msg: to_binary(&ReceiveNftMsg::AcceptCollectionBid {
+            bidder: "alice".to_string(),
+            finder: Some("finder".to_string()),
+        })
+        .unwrap(),
+    };
+    let msg = ExecuteMsg::ReceiveNft(rcv_msg);
+    let res = execute(deps.as_mut(), at(10), mock_info(COLLECTION, &[]), msg).unwrap();
+    assert_eq!(
+        bank_sends(&res),
+        vec![
+            (FEE_RECIPIENT.to_string(), 20),
+            ("finder".to_string(), 50),
+            ("owner".to_string(), 930),
+        ]
+    );
+}
+
+#[test]
+fn test_sale_in_added_denom() {
+    let mut deps = setup();
+    let msg = SudoMsg::AddDenom {
+        denom: "uatom".to_string(),
+        min_price: Uint128::new(5),
+    };
+    sudo(deps.as_mut(), mock_env(), msg).unwrap();
+
+    let mut info = ask_info(SaleType::FixedPrice, "1", 4, 100);
+    info.price = Uint128::new(4);
+    info.denom = Denom::Native("uatom".to_string());
+    let rcv_msg = Cw721ReceiveMsg {
+        sender: SELLER.to_string(),
+        token_id: "1".to_string(),
+        msg: to_binary(&ReceiveNftMsg::SetAsk(info.clone())).unwrap(),
+    };
+    let err = execute(
+        deps.as_mut(),
+        at(0),
+        mock_info(COLLECTION, &coins(10, DENOM)),
+        ExecuteMsg::ReceiveNft(rcv_msg),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::PriceTooSmall(Uint128::new(4)));
+    info.price = Uint128::new(1000);
+    set_ask(deps.as_mut(), at(0), info);
+
+    // Bids on the ask must be made in its denom
+    let msg = ExecuteMsg::SetBid {
+        collection: COLLECTION.to_string(),
+        token_id: "1".to_string(),
+        expires: None,
+        finder: None,
+    };
+    let err = execute(
+        deps.as_mut(),
+        at(10),
+        mock_info("alice", &coins(1000, DENOM)),
+        msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidDenom(DENOM.to_string()));
+
+    // Fees and the seller's share are paid in the denom of the sale
+    let res = execute(
+        deps.as_mut(),
+        at(10),
+        mock_info("alice", &coins(1000, "uatom")),
+        msg,
+    )
+    .unwrap();
+    let sends = res
+        .messages
+        .iter()
+        .filter_map(|sub_msg| match &sub_msg.msg {
+            CosmosMsg::Bank(BankMsg::Send { to_address, amount }) => {
+                Some((to_address.clone(), amount.clone()))
+            }
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        sends,
+        vec![
+            (FEE_RECIPIENT.to_string(), coins(20, "uatom")),
+            (SELLER.to_string(), coins(980, "uatom")),
+        ]
+    );
+
+    // Once removed, the denom is no longer accepted
+    let msg = SudoMsg::RemoveDenom {
+        denom: "uatom".to_string(),
+    };
+    sudo(deps.as_mut(), mock_env(), msg).unwrap();
+    let msg = ExecuteMsg::SetCollectionBid {
+        collection: COLLECTION.to_string(),
+        expires: 100,
+        finders_fee_bps: None,
+    };
+    let err = execute(
+        deps.as_mut(),
+        at(20),
+        mock_info("bob", &coins(100, "uatom")),
+        msg,
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidDenom("uatom".to_string()));
+}
+
+/// Recipients and amounts of the CW20 transfers of `token` in a response
+fn cw20_transfers(res: &Response, token: &str) -> Vec<(String, u128)> {
+    hook_msgs(res, token)
+        .iter()
+        .map(|msg| match from_binary(msg).unwrap() {
+            Cw20ExecuteMsg::Transfer { recipient, amount } => (recipient, amount.u128()),
+            msg => panic!("unexpected cw20 message {:?}", msg),
+        })
+        .collect()
+}
+
+#[test]
+fn test_buy_with_cw20_token() {
+    let mut deps = setup();
+    let msg = SudoMsg::AddCw20Token {
+        token: "token".to_string(),
+        min_price: Uint128::new(1),
+    };
+    sudo(deps.as_mut(), mock_env(), msg).unwrap();
+    let msg = SudoMsg::AddSaleHook {
+        hook: "sale_hook".to_string(),
+    };
+    sudo(deps.as_mut(), mock_env(), msg).unwrap();
+
+    let mut info = ask_info(SaleType::FixedPrice, "1", 1000, 100);
+    info.denom = Denom::Cw20(Addr::unchecked("token"));
+    set_ask(deps.as_mut(), at(0), info);
+
+    let mut receive_msg = |token: &str| {
+        let cw20_msg = Cw20ReceiveMsg {
+            sender: "alice".to_string(),
+            amount: Uint128::new(1000),
+            msg: to_binary(&ReceiveCw20Msg::SetBid {
+                collection: COLLECTION.to_string(),
+                token_id: "1".to_string(),
+                expires: None,
+                finder: None,
+            })
+            .unwrap(),
+        };
+        execute(
+            deps.as_mut(),
+            at(10),
+            mock_info(token, &[]),
+            ExecuteMsg::Receive(cw20_msg),
+        )
+    };
+    let err = receive_msg("other").unwrap_err();
+    assert_eq!(err, ContractError::InvalidDenom("other".to_string()));
+
+    // Fees and the seller's share are transferred in the token
+    let res = receive_msg("token").unwrap();
+    assert!(bank_sends(&res).is_empty());
+    assert_eq!(
+        cw20_transfers(&res, "token"),
+        vec![(FEE_RECIPIENT.to_string(), 20), (SELLER.to_string(), 980)]
+    );
+
+    // Sale hooks still get the price as a coin, along with the token
+    let hook_msg = SaleHookMsg {
+        collection: COLLECTION.to_string(),
+        token_id: "1".to_string(),
+        price: coin(1000, "token"),
+        cw20_token: Some("token".to_string()),
+        seller: SELLER.to_string(),
+        buyer: "alice".to_string(),
+    };
+    assert_eq!(
+        hook_msgs(&res, "sale_hook"),
+        vec![hook_msg.into_binary().unwrap()]
+    );
+}
+
+#[test]
+fn test_late_bids_extend_auction() {
+    let mut deps = setup();
+    set_ask(
+        deps.as_mut(),
+        at(0),
+        ask_info(SaleType::Auction, "1", 100, 1000),
+    );
+    let key = ask_key(&Addr::unchecked(COLLECTION), &"1".to_string());
+    let expires_at = |deps: &OwnedDeps<MockStorage, MockApi, MockQuerier>| {
+        let ask = asks().load(&deps.storage, key.clone()).unwrap();
+        ask.expires_at.seconds() - mock_env().block.time.seconds()
+    };
+
+    // Bids before the extension window leave the end of the auction alone
+    let res = set_bid(deps.as_mut(), at(10), "alice", "1", 100, None);
+    assert!(hook_msgs(&res, ASK_HOOK).is_empty());
+    assert_eq!(expires_at(&deps), 1000);
+
+    let res = set_bid(deps.as_mut(), at(950), "bob", "1", 150, None);
+    let ask = asks().load(&deps.storage, key.clone()).unwrap();
+    assert_eq!(
+        hook_msgs(&res, ASK_HOOK),
+        vec![AskHookMsg::new(ask.clone())
+            .into_binary(HookAction::Update)
+            .unwrap()]
+    );
+    let event = res.events.iter().find(|e| e.ty == "set-bid").unwrap();
+    assert!(event
+        .attributes
+        .iter()
+        .any(|attr| attr.key == "expires_at" && attr.value == ask.expires_at.to_string()));
+    assert_eq!(expires_at(&deps), 1120);
+
+    // Extensions stop once they add up to the max extension
+    set_bid(deps.as_mut(), at(1100), "carol", "1", 200, None);
+    assert_eq!(expires_at(&deps), 1240);
+    set_bid(deps.as_mut(), at(1200), "dave", "1", 250, None);
+    assert_eq!(expires_at(&deps), 1300);
+    let res = set_bid(deps.as_mut(), at(1290), "erin", "1", 300, None);
+    assert!(hook_msgs(&res, ASK_HOOK).is_empty());
+    assert_eq!(expires_at(&deps), 1300);
+}
+
+#[test]
+fn test_min_bid_increment() {
+    let mut deps = setup();
+    set_ask(
+        deps.as_mut(),
+        at(0),
+        ask_info(SaleType::Auction, "1", 100, 1000),
+    );
+    let mut info = ask_info(SaleType::Auction, "2", 100, 1000);
+    info.min_bid_increment = Some(BidIncrement::Amount(Uint128::new(25)));
+    set_ask(deps.as_mut(), at(0), info);
+
+    let min_next_bid = |deps: &OwnedDeps<MockStorage, MockApi, MockQuerier>, token_id: &str| {
+        let msg = QueryMsg::MinNextBid {
+            collection: COLLECTION.to_string(),
+            token_id: token_id.to_string(),
+        };
+        let res: MinNextBidResponse =
+            from_binary(&query(deps.as_ref(), at(10), msg).unwrap()).unwrap();
+        res.price.u128()
+    };
+    let try_bid = |deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>,
+                   bidder: &str,
+                   token_id: &str,
+                   price: u128| {
+        let msg = ExecuteMsg::SetBid {
+            collection: COLLECTION.to_string(),
+            token_id: token_id.to_string(),
+            expires: None,
+            finder: None,
+        };
+        execute(
+            deps.as_mut(),
+            at(10),
+            mock_info(bidder, &coins(price, DENOM)),
+            msg,
+        )
+    };
+
+    // The first bid only needs to meet the starting price
+    assert_eq!(min_next_bid(&deps, "1"), 100);
+    try_bid(&mut deps, "alice", "1", 100).unwrap();
+
+    // Governance requires a 10% raise, unless the ask sets its own increment
+    assert_eq!(min_next_bid(&deps, "1"), 110);
+    let err = try_bid(&mut deps, "bob", "1", 109).unwrap_err();
+    assert_eq!(err, ContractError::BidIncrementTooSmall(Uint128::new(110)));
+    try_bid(&mut deps, "bob", "1", 110).unwrap();
+
+    try_bid(&mut deps, "alice", "2", 100).unwrap();
+    assert_eq!(min_next_bid(&deps, "2"), 125);
+    let err = try_bid(&mut deps, "bob", "2", 124).unwrap_err();
+    assert_eq!(err, ContractError::BidIncrementTooSmall(Uint128::new(125)));
+    try_bid(&mut deps, "bob", "2", 125).unwrap();
+}
+
+fn accept_auction(deps: DepsMut, env: Env, token_id: &str) -> Response {
+    let msg = ExecuteMsg::AcceptBid {
+        collection: COLLECTION.to_string(),
+        token_id: token_id.to_string(),
+        bidder: None,
+        finder: None,
+    };
+    execute(deps, env, mock_info(SELLER, &[]), msg).unwrap()
+}
+
+#[test]
+fn test_auction_reserve_price() {
+    let mut deps = setup();
+    let msg = SudoMsg::AddSaleHook {
+        hook: "sale_hook".to_string(),
+    };
+    sudo(deps.as_mut(), mock_env(), msg).unwrap();
+    for token_id in ["1", "2"] {
+        let mut info = ask_info(SaleType::Auction, token_id, 100, 1000);
+        info.reserve_price = Some(Uint128::new(200));
+        info.hide_reserve_price = true;
+        set_ask(deps.as_mut(), at(0), info);
+    }
+
+    // A hidden reserve is left out of the ask
+    let key = ask_key(&Addr::unchecked(COLLECTION), &"1".to_string());
+    assert_eq!(asks().load(&deps.storage, key).unwrap().reserve_price, None);
+
+    // Below the reserve, the bid is refunded and the NFT returned to the seller
+    set_bid(deps.as_mut(), at(10), "alice", "1", 150, None);
+    let res = accept_auction(deps.as_mut(), at(1000), "1");
+    assert_eq!(bank_sends(&res), vec![("alice".to_string(), 150)]);
+    assert_eq!(nft_transfers(&res), vec![SELLER.to_string()]);
+    assert!(hook_msgs(&res, "sale_hook").is_empty());
+
+    // Above it, the NFT sells at the winning bid
+    set_bid(deps.as_mut(), at(10), "bob", "2", 250, None);
+    let res = accept_auction(deps.as_mut(), at(1000), "2");
+    assert_eq!(
+        bank_sends(&res),
+        vec![(FEE_RECIPIENT.to_string(), 5), (SELLER.to_string(), 245)]
+    );
+    assert_eq!(nft_transfers(&res), vec!["bob".to_string()]);
+    let hook_msg = SaleHookMsg::new(
+        COLLECTION.to_string(),
+        "2".to_string(),
+        Uint128::new(250),
+        Denom::Native(DENOM.to_string()),
+        SELLER.to_string(),
+        "bob".to_string(),
+    );
+    assert_eq!(
+        hook_msgs(&res, "sale_hook"),
+        vec![hook_msg.into_binary().unwrap()]
+    );
+}
+
+#[test]
+fn test_auction_buy_now_price() {
+    let mut deps = setup();
+    let mut info = ask_info(SaleType::Auction, "1", 100, 1000);
+    info.buy_now_price = Some(Uint128::new(100));
+    let rcv_msg = Cw721ReceiveMsg {
+        sender: SELLER.to_string(),
+        token_id: "1".to_string(),
+        msg: to_binary(&ReceiveNftMsg::SetAsk(info.clone())).unwrap(),
+    };
+    let err = execute(
+        deps.as_mut(),
+        at(0),
+        mock_info(COLLECTION, &coins(10, DENOM)),
+        ExecuteMsg::ReceiveNft(rcv_msg),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidBuyNowPrice {});
+    info.buy_now_price = Some(Uint128::new(500));
+    set_ask(deps.as_mut(), at(0), info);
+    let key = ask_key(&Addr::unchecked(COLLECTION), &"1".to_string());
+    set_bid(deps.as_mut(), at(10), "alice", "1", 150, None);
+
+    let msg = ExecuteMsg::SetBid {
+        collection: COLLECTION.to_string(),
+        token_id: "1".to_string(),
+        expires: None,
+        finder: None,
+    };
+    let err = execute(
+        deps.as_mut(),
+        at(20),
+        mock_info("bob", &coins(600, DENOM)),
+        msg,
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidPrice {});
+
+    // The buy now price settles the auction right away, refunding the highest bidder
+    let mut ask = asks().load(&deps.storage, key.clone()).unwrap();
+    let res = set_bid(deps.as_mut(), at(20), "bob", "1", 500, None);
+    assert_eq!(
+        bank_sends(&res),
+        vec![
+            ("alice".to_string(), 150),
+            (FEE_RECIPIENT.to_string(), 10),
+            (SELLER.to_string(), 490),
+        ]
+    );
+    assert_eq!(nft_transfers(&res), vec!["bob".to_string()]);
+    ask.price = Uint128::new(500);
+    assert_eq!(
+        hook_msgs(&res, ASK_HOOK),
+        vec![AskHookMsg::new(ask)
+            .into_binary(HookAction::Delete)
+            .unwrap()]
+    );
+    assert!(!asks().has(&deps.storage, key));
+}
+
+fn settle_auction(deps: DepsMut, env: Env, token_id: &str) -> Result<Response, ContractError> {
+    let msg = ExecuteMsg::SettleAuction {
+        collection: COLLECTION.to_string(),
+        token_id: token_id.to_string(),
+    };
+    execute(deps, env, mock_info("keeper", &[]), msg)
+}
+
+#[test]
+fn test_settle_auction() {
+    let mut deps = setup();
+    for token_id in ["1", "2"] {
+        set_ask(
+            deps.as_mut(),
+            at(0),
+            ask_info(SaleType::Auction, token_id, 100, 1000),
+        );
+    }
+    set_bid(deps.as_mut(), at(10), "alice", "1", 150, None);
+
+    let err = settle_auction(deps.as_mut(), at(999), "1").unwrap_err();
+    assert_eq!(err, ContractError::AuctionNotEnded {});
+
+    // Anyone can settle an ended auction, selling the NFT to the highest bidder
+    let res = settle_auction(deps.as_mut(), at(1000), "1").unwrap();
+    assert_eq!(
+        bank_sends(&res),
+        vec![(FEE_RECIPIENT.to_string(), 3), (SELLER.to_string(), 147)]
+    );
+    assert_eq!(nft_transfers(&res), vec!["alice".to_string()]);
+    let err = settle_auction(deps.as_mut(), at(1000), "1").unwrap_err();
+    assert_eq!(err, ContractError::AskNotFound {});
+
+    // Without bids the NFT goes back to the seller
+    let res = settle_auction(deps.as_mut(), at(1000), "2").unwrap();
+    assert!(bank_sends(&res).is_empty());
+    assert_eq!(nft_transfers(&res), vec![SELLER.to_string()]);
+    let key = ask_key(&Addr::unchecked(COLLECTION), &"2".to_string());
+    assert!(!asks().has(&deps.storage, key));
+}
+
+#[test]
+fn test_dutch_auction() {
+    let mut deps = setup();
+    for (token_id, decay) in [
+        ("1", PriceDecay::Linear),
+        ("2", PriceDecay::Stepped { interval: 300 }),
+    ] {
+        let mut info = ask_info(SaleType::DutchAuction, token_id, 1000, 1000);
+        info.dutch_auction = Some(DutchAuction {
+            end_price: Uint128::new(200),
+            decay,
+        });
+        set_ask(deps.as_mut(), at(0), info);
+    }
+
+    let price = |deps: &OwnedDeps<MockStorage, MockApi, MockQuerier>, token_id: &str| {
+        let msg = QueryMsg::DutchAuctionPrice {
+            collection: COLLECTION.to_string(),
+            token_id: token_id.to_string(),
+        };
+        let res: DutchAuctionPriceResponse =
+            from_binary(&query(deps.as_ref(), at(500), msg).unwrap()).unwrap();
+        res.price.u128()
+    };
+    assert_eq!(price(&deps, "1"), 600);
+    assert_eq!(price(&deps, "2"), 760);
+
+    let msg = ExecuteMsg::SetBid {
+        collection: COLLECTION.to_string(),
+        token_id: "1".to_string(),
+        expires: None,
+        finder: None,
+    };
+    let err = execute(
+        deps.as_mut(),
+        at(500),
+        mock_info("alice", &coins(550, DENOM)),
+        msg,
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::PriceTooSmall(Uint128::new(550)));
+
+    // The first bid paying the current price buys the NFT, and the excess is refunded
+    let key = ask_key(&Addr::unchecked(COLLECTION), &"1".to_string());
+    let mut ask = asks().load(&deps.storage, key.clone()).unwrap();
+    let res = set_bid(deps.as_mut(), at(500), "alice", "1", 700, None);
+    assert_eq!(
+        bank_sends(&res),
+        vec![
+            ("alice".to_string(), 100),
+            (FEE_RECIPIENT.to_string(), 12),
+            (SELLER.to_string(), 588),
+        ]
+    );
+    assert_eq!(nft_transfers(&res), vec!["alice".to_string()]);
+    ask.price = Uint128::new(600);
+    assert_eq!(
+        hook_msgs(&res, ASK_HOOK),
+        vec![AskHookMsg::new(ask)
+            .into_binary(HookAction::Delete)
+            .unwrap()]
+    );
+    assert!(!asks().has(&deps.storage, key));
+}
+
+fn sealed_ask_info(pricing: SealedAuctionPricing) -> AskInfo {
+    AskInfo {
+        sealed_auction: Some(SealedAuctionInfo {
+            pricing,
+            reveal_duration: 400,
+        }),
+        ..ask_info(SaleType::SealedAuction, "1", 100, 1000)
+    }
+}
+
+fn commit_bid(deps: DepsMut, env: Env, bidder: &str, deposit: u128, price: u128) {
+    let commitment = sealed_bid_commitment(&Addr::unchecked(bidder), Uint128::new(price), bidder);
+    let msg = ExecuteMsg::CommitBid {
+        collection: COLLECTION.to_string(),
+        token_id: "1".to_string(),
+        commitment,
+    };
+    execute(deps, env, mock_info(bidder, &coins(deposit, DENOM)), msg).unwrap();
+}
+
+fn reveal_bid(
+    deps: DepsMut,
+    env: Env,
+    bidder: &str,
+    price: u128,
+    salt: &str,
+) -> Result<Response, ContractError> {
+    let msg = ExecuteMsg::RevealBid {
+        collection: COLLECTION.to_string(),
+        token_id: "1".to_string(),
+        price: Uint128::new(price),
+        salt: salt.to_string(),
+    };
+    execute(deps, env, mock_info(bidder, &[]), msg)
+}
+
+#[test]
+fn test_sealed_auction_second_price() {
+    let mut deps = setup();
+    set_ask(
+        deps.as_mut(),
+        at(0),
+        sealed_ask_info(SealedAuctionPricing::SecondPrice),
+    );
+
+    commit_bid(deps.as_mut(), at(10), "alice", 500, 400);
+    commit_bid(deps.as_mut(), at(20), "bob", 300, 250);
+    commit_bid(deps.as_mut(), at(30), "carol", 200, 150);
+
+    // Bids can only be revealed in the reveal phase, with the salt they were committed with
+    let err = reveal_bid(deps.as_mut(), at(100), "alice", 400, "alice").unwrap_err();
+    assert_eq!(err, ContractError::InvalidAuctionPhase {});
+    let err = reveal_bid(deps.as_mut(), at(700), "alice", 400, "bob").unwrap_err();
+    assert_eq!(err, ContractError::InvalidCommitment {});
+    reveal_bid(deps.as_mut(), at(700), "alice", 400, "alice").unwrap();
+    reveal_bid(deps.as_mut(), at(700), "bob", 250, "bob").unwrap();
+
+    let res = settle_auction(deps.as_mut(), at(1000), "1").unwrap();
+
+    // Carol's unrevealed deposit is slashed, Bob is refunded, and Alice wins at Bob's price
+    assert_eq!(
+        bank_sends(&res),
+        vec![
+            (FEE_RECIPIENT.to_string(), 200),
+            ("bob".to_string(), 300),
+            ("alice".to_string(), 250),
+            (FEE_RECIPIENT.to_string(), 5),
+            (SELLER.to_string(), 245),
+        ]
+    );
+    assert_eq!(nft_transfers(&res), vec!["alice".to_string()]);
+
+    let ask = asks().may_load(
+        &deps.storage,
+        ask_key(&Addr::unchecked(COLLECTION), &"1".to_string()),
+    );
+    assert_eq!(ask.unwrap(), None);
+    let sealed_bids = SEALED_BIDS
+        .prefix((Addr::unchecked(COLLECTION), "1".to_string()))
+        .keys_raw(&deps.storage, None, None, StorageOrder::Ascending)
+        .count();
+    assert_eq!(sealed_bids, 0);
+}
+
+#[test]
+fn test_sealed_auction_first_price_tie() {
+    let mut deps = setup();
+    set_ask(
+        deps.as_mut(),
+        at(0),
+        sealed_ask_info(SealedAuctionPricing::FirstPrice),
+    );
+
+    commit_bid(deps.as_mut(), at(10), "bob", 300, 200);
+    commit_bid(deps.as_mut(), at(20), "alice", 300, 200);
+    reveal_bid(deps.as_mut(), at(700), "alice", 200, "alice").unwrap();
+    reveal_bid(deps.as_mut(), at(700), "bob", 200, "bob").unwrap();
+
+    let res = settle_auction(deps.as_mut(), at(1000), "1").unwrap();
+
+    // The tie goes to Bob's earlier commitment, who pays his own bid
+    assert_eq!(
+        bank_sends(&res),
+        vec![
+            ("alice".to_string(), 300),
+            ("bob".to_string(), 100),
+            (FEE_RECIPIENT.to_string(), 4),
+            (SELLER.to_string(), 196),
+        ]
+    );
+    assert_eq!(nft_transfers(&res), vec!["bob".to_string()]);
+}
+
+#[test]
+fn test_sealed_auction_without_reveals() {
+    let mut deps = setup();
+    set_ask(
+        deps.as_mut(),
+        at(0),
+        sealed_ask_info(SealedAuctionPricing::SecondPrice),
+    );
+
+    commit_bid(deps.as_mut(), at(10), "alice", 500, 400);
+
+    let res = settle_auction(deps.as_mut(), at(1000), "1").unwrap();
+
+    assert_eq!(bank_sends(&res), vec![(FEE_RECIPIENT.to_string(), 500)]);
+    assert_eq!(nft_transfers(&res), vec![SELLER.to_string()]);
+}
+
+#[test]
+fn test_sealed_auction_commitment_cap() {
+    let mut deps = setup();
+    set_ask(
+        deps.as_mut(),
+        at(0),
+        sealed_ask_info(SealedAuctionPricing::FirstPrice),
+    );
+    for i in 0..MAX_SEALED_BIDS {
+        commit_bid(deps.as_mut(), at(10), &format!("bidder{}", i), 100, 100);
+    }
+
+    // Bidders can still replace their commitment, but no one else can commit
+    commit_bid(deps.as_mut(), at(20), "bidder0", 200, 150);
+    let msg = ExecuteMsg::CommitBid {
+        collection: COLLECTION.to_string(),
+        token_id: "1".to_string(),
+        commitment: sealed_bid_commitment(&Addr::unchecked("alice"), Uint128::new(100), "alice"),
+    };
+    let err = execute(
+        deps.as_mut(),
+        at(20),
+        mock_info("alice", &coins(100, DENOM)),
+        msg,
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::TooManySealedBids {});
+}
+
+fn set_proxy_bid(deps: DepsMut, env: Env, bidder: &str, max_price: u128) -> Response {
+    let msg = ExecuteMsg::SetProxyBid {
+        collection: COLLECTION.to_string(),
+        token_id: "1".to_string(),
+        finder: None,
+    };
+    execute(deps, env, mock_info(bidder, &coins(max_price, DENOM)), msg).unwrap()
+}
+
+/// Highest bidder and bid of the auction of token "1"
+fn highest_bid(deps: &OwnedDeps<MockStorage, MockApi, MockQuerier>) -> (String, u128) {
+    let ask = asks()
+        .load(
+            &deps.storage,
+            ask_key(&Addr::unchecked(COLLECTION), &"1".to_string()),
+        )
+        .unwrap();
+    (
+        ask.max_bidder.unwrap().to_string(),
+        ask.max_bid.unwrap().u128(),
+    )
+}
+
+/// Auction of token "1" raised by steps of 10
+fn proxy_ask_info() -> AskInfo {
+    AskInfo {
+        min_bid_increment: Some(BidIncrement::Amount(Uint128::new(10))),
+        ..ask_info(SaleType::Auction, "1", 100, 1000)
+    }
+}
+
+#[test]
+fn test_proxy_bid_escalation() {
+    let mut deps = setup();
+    set_ask(deps.as_mut(), at(0), proxy_ask_info());
+
+    // Without competition a proxy bid starts at the ask price
+    let res = set_proxy_bid(deps.as_mut(), at(10), "alice", 300);
+    assert!(bank_sends(&res).is_empty());
+    assert_eq!(highest_bid(&deps), ("alice".to_string(), 100));
+
+    // A lower bid is outbid right away, and the proxy bid raises to the increment above it
+    let res = set_bid(deps.as_mut(), at(20), "bob", "1", 150, None);
+    assert_eq!(bank_sends(&res), vec![("bob".to_string(), 150)]);
+    assert_eq!(highest_bid(&deps), ("alice".to_string(), 160));
+    let key = bid_key(
+        &Addr::unchecked(COLLECTION),
+        &"1".to_string(),
+        &Addr::unchecked("alice"),
+    );
+    assert_eq!(bids().load(&deps.storage, key).unwrap().price.u128(), 160);
+
+    // A higher proxy bid refunds the escrow of the previous one, and leads by the increment
+    let res = set_proxy_bid(deps.as_mut(), at(30), "bob", 500);
+    assert_eq!(bank_sends(&res), vec![("alice".to_string(), 300)]);
+    assert_eq!(highest_bid(&deps), ("bob".to_string(), 310));
+
+    // The winner pays their bid, and the rest of their escrow is refunded
+    let res = settle_auction(deps.as_mut(), at(1000), "1").unwrap();
+    assert_eq!(
+        bank_sends(&res),
+        vec![
+            ("bob".to_string(), 190),
+            (FEE_RECIPIENT.to_string(), 6),
+            (SELLER.to_string(), 304),
+        ]
+    );
+    assert_eq!(nft_transfers(&res), vec!["bob".to_string()]);
+}
+
+#[test]
+fn test_proxy_bid_tie() {
+    let mut deps = setup();
+    set_ask(deps.as_mut(), at(0), proxy_ask_info());
+    set_proxy_bid(deps.as_mut(), at(10), "alice", 300);
+
+    // The proxy bid wins a tie at its max price, since it was placed first
+    let res = set_bid(deps.as_mut(), at(20), "bob", "1", 300, None);
+    assert_eq!(bank_sends(&res), vec![("bob".to_string(), 300)]);
+    assert_eq!(highest_bid(&deps), ("alice".to_string(), 300));
+
+    let res = set_bid(deps.as_mut(), at(30), "bob", "1", 310, None);
+    assert_eq!(bank_sends(&res), vec![("alice".to_string(), 300)]);
+    assert_eq!(highest_bid(&deps), ("bob".to_string(), 310));
+}
+
+#[test]
+fn test_accept_auction_bid_early() {
+    let mut deps = setup();
+    set_ask(
+        deps.as_mut(),
+        at(0),
+        ask_info(SaleType::Auction, "1", 100, 1000),
+    );
+    let mut info = ask_info(SaleType::Auction, "2", 100, 1000);
+    info.allow_early_accept = true;
+    info.reserve_price = Some(Uint128::new(200));
+    set_ask(deps.as_mut(), at(0), info);
+
+    let msg = |token_id: &str| ExecuteMsg::AcceptBid {
+        collection: COLLECTION.to_string(),
+        token_id: token_id.to_string(),
+        bidder: None,
+        finder: None,
+    };
+    let seller = mock_info(SELLER, &[]);
+
+    // Only asks that opt in can be accepted before they end
+    set_bid(deps.as_mut(), at(10), "alice", "1", 300, None);
+    let err = execute(deps.as_mut(), at(20), seller.clone(), msg("1")).unwrap_err();
+    assert_eq!(err, ContractError::AuctionNotEnded {});
+
+    let err = execute(deps.as_mut(), at(20), seller.clone(), msg("2")).unwrap_err();
+    assert_eq!(err, ContractError::AuctionHasNoBids {});
+    set_bid(deps.as_mut(), at(20), "alice", "2", 150, None);
+    let err = execute(deps.as_mut(), at(30), seller.clone(), msg("2")).unwrap_err();
+    assert_eq!(err, ContractError::ReserveNotMet {});
+
+    set_bid(deps.as_mut(), at(30), "bob", "2", 250, None);
+    let res = execute(deps.as_mut(), at(40), seller, msg("2")).unwrap();
+    assert_eq!(
+        bank_sends(&res),
+        vec![(FEE_RECIPIENT.to_string(), 5), (SELLER.to_string(), 245)]
+    );
+    assert_eq!(nft_transfers(&res), vec!["bob".to_string()]);
+    let key = ask_key(&Addr::unchecked(COLLECTION), &"2".to_string());
+    assert!(!asks().has(&deps.storage, key));
+}
+
+#[test]
+fn test_cancel_auction() {
+    let mut deps = setup();
+    for token_id in ["1", "2"] {
+        set_ask(
+            deps.as_mut(),
+            at(0),
+            ask_info(SaleType::Auction, token_id, 100, 1000),
+        );
+    }
+    let remove_ask = |token_id: &str| ExecuteMsg::RemoveAsk {
+        collection: COLLECTION.to_string(),
+        token_id: token_id.to_string(),
+    };
+
+    // An auction without bids is cancelled for free
+    let res = execute(
+        deps.as_mut(),
+        at(10),
+        mock_info(SELLER, &[]),
+        remove_ask("1"),
+    )
+    .unwrap();
+    assert!(bank_sends(&res).is_empty());
+    assert_eq!(nft_transfers(&res), vec![SELLER.to_string()]);
+
+    // With bids it takes a penalty set by governance
+    set_bid(deps.as_mut(), at(10), "alice", "2", 200, None);
+    let err = execute(
+        deps.as_mut(),
+        at(20),
+        mock_info(SELLER, &[]),
+        remove_ask("2"),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::AuctionNotRemove {});
+
+    SUDO_PARAMS
+        .update(&mut deps.storage, |mut params| -> StdResult<_> {
+            params.auction_cancel_penalty_percent = Decimal::percent(500);
+            Ok(params)
+        })
+        .unwrap();
+    let err = execute(
+        deps.as_mut(),
+        at(20),
+        mock_info(SELLER, &coins(5, DENOM)),
+        remove_ask("2"),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidCancelPenalty(Uint128::new(5)));
+
+    // The penalty is paid to the highest bidder along with the refund of the bid
+    let res = execute(
+        deps.as_mut(),
+        at(20),
+        mock_info(SELLER, &coins(10, DENOM)),
+        remove_ask("2"),
+    )
+    .unwrap();
+    assert_eq!(bank_sends(&res), vec![("alice".to_string(), 210)]);
+    assert_eq!(nft_transfers(&res), vec![SELLER.to_string()]);
+    let key = bid_key(
+        &Addr::unchecked(COLLECTION),
+        &"2".to_string(),
+        &Addr::unchecked("alice"),
+    );
+    assert!(!bids().has(&deps.storage, key));
+}
+
+#[test]
+fn test_remove_stale_asks() {
+    let mut deps = setup();
+    SUDO_PARAMS
+        .update(&mut deps.storage, |mut params| -> StdResult<_> {
+            params.operators = vec![Addr::unchecked("operator")];
+            Ok(params)
+        })
+        .unwrap();
+    set_ask(
+        deps.as_mut(),
+        at(0),
+        ask_info(SaleType::FixedPrice, "1", 100, 100),
+    );
+    set_ask(
+        deps.as_mut(),
+        at(0),
+        ask_info(SaleType::Auction, "2", 100, 100),
+    );
+    set_bid(deps.as_mut(), at(10), "alice", "2", 150, None);
+
+    let remove_stale_asks = |token_ids: &[&str]| ExecuteMsg::RemoveStaleAsks {
+        collection: COLLECTION.to_string(),
+        token_ids: token_ids.iter().map(|id| id.to_string()).collect(),
+    };
+    let err = execute(
+        deps.as_mut(),
+        at(100),
+        mock_info("alice", &[]),
+        remove_stale_asks(&["1", "2"]),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::UnauthorizedOperator {});
+    let err = execute(
+        deps.as_mut(),
+        at(99),
+        mock_info("operator", &[]),
+        remove_stale_asks(&["1", "2"]),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::AskNotExpired {});
+
+    // The fixed price NFT goes back to the seller, and the auction is sold to its highest bidder
+    let res = execute(
+        deps.as_mut(),
+        at(100),
+        mock_info("operator", &[]),
+        remove_stale_asks(&["1", "2"]),
+    )
+    .unwrap();
+    assert_eq!(
+        nft_transfers(&res),
+        vec![SELLER.to_string(), "alice".to_string()]
+    );
+    assert_eq!(
+        bank_sends(&res),
+        vec![(FEE_RECIPIENT.to_string(), 3), (SELLER.to_string(), 147)]
+    );
+    for token_id in ["1", "2"] {
+        let key = ask_key(&Addr::unchecked(COLLECTION), &token_id.to_string());
+        assert!(!asks().has(&deps.storage, key));
+    }
+}
+
+fn process_expired(deps: DepsMut, env: Env, keeper: &str, limit: Option<u32>) -> Response {
+    let msg = ExecuteMsg::ProcessExpired { limit };
+    execute(deps, env, mock_info(keeper, &[]), msg).unwrap()
+}
+
+fn listing_fees(deps: &OwnedDeps<MockStorage, MockApi, MockQuerier>) -> u128 {
+    LISTING_FEES.load(&deps.storage, DENOM).unwrap().u128()
+}
+
+#[test]
+fn test_process_expired_rewards_asks() {
+    let mut deps = setup();
+    set_ask(
+        deps.as_mut(),
+        at(0),
+        ask_info(SaleType::FixedPrice, "1", 100, 100),
+    );
+    set_ask(
+        deps.as_mut(),
+        at(0),
+        ask_info(SaleType::FixedPrice, "2", 100, 100),
+    );
+    set_bid(deps.as_mut(), at(0), "bidder", "3", 50, Some(100));
+    assert_eq!(listing_fees(&deps), 20);
+
+    // The expired bid is refunded, but only the asks earn the keeper reward
+    let res = process_expired(deps.as_mut(), at(100), "keeper", None);
+    assert_eq!(
+        bank_sends(&res),
+        vec![("bidder".to_string(), 50), ("keeper".to_string(), 10)]
+    );
+    assert_eq!(
+        nft_transfers(&res),
+        vec![SELLER.to_string(), SELLER.to_string()]
+    );
+    assert_eq!(listing_fees(&deps), 10);
+}
+
+#[test]
+fn test_process_expired_own_asks() {
+    let mut deps = setup();
+    set_ask(
+        deps.as_mut(),
+        at(0),
+        ask_info(SaleType::FixedPrice, "1", 100, 100),
+    );
+
+    // Sellers are not rewarded for processing their own asks
+    let res = process_expired(deps.as_mut(), at(100), SELLER, None);
+    assert_eq!(bank_sends(&res), vec![]);
+    assert_eq!(nft_transfers(&res), vec![SELLER.to_string()]);
+    assert_eq!(listing_fees(&deps), 10);
+}
+
+#[test]
+fn test_process_expired_limit() {
+    let mut deps = setup();
+    set_ask(
+        deps.as_mut(),
+        at(0),
+        ask_info(SaleType::FixedPrice, "1", 100, 100),
+    );
+    set_ask(
+        deps.as_mut(),
+        at(0),
+        ask_info(SaleType::FixedPrice, "2", 100, 200),
+    );
+    set_ask(
+        deps.as_mut(),
+        at(0),
+        ask_info(SaleType::FixedPrice, "3", 100, 300),
+    );
+
+    // Orders are processed in order of expiration, up to the limit
+    let res = process_expired(deps.as_mut(), at(300), "keeper", Some(2));
+    assert_eq!(bank_sends(&res), vec![("keeper".to_string(), 10)]);
+    assert_eq!(
+        nft_transfers(&res),
+        vec![SELLER.to_string(), SELLER.to_string()]
+    );
+
+    let collection = Addr::unchecked(COLLECTION);
+    let ask = asks().may_load(&deps.storage, ask_key(&collection, &"3".to_string()));
+    assert!(ask.unwrap().is_some());
+}
+
+#[test]
+fn test_process_expired_skips_leading_auction_bids() {
+    let mut deps = setup();
+    // An offer made before the NFT was listed in an auction is refunded when it expires
+    set_bid(deps.as_mut(), at(0), "alice", "1", 50, Some(100));
+    set_ask(
+        deps.as_mut(),
+        at(0),
+        ask_info(SaleType::Auction, "1", 100, 1000),
+    );
+    set_bid(deps.as_mut(), at(0), "carol", "1", 150, None);
+    set_bid(deps.as_mut(), at(0), "bob", "2", 60, Some(200));
+
+    // The leading auction bid is left to the auction, and does not count against the limit
+    let res = process_expired(deps.as_mut(), at(300), "keeper", Some(2));
+    assert_eq!(
+        bank_sends(&res),
+        vec![("alice".to_string(), 50), ("bob".to_string(), 60)]
+    );
+
+    let key = bid_key(
+        &Addr::unchecked(COLLECTION),
+        &"1".to_string(),
+        &Addr::unchecked("carol"),
+    );
+    assert!(bids().has(&deps.storage, key));
+}
+
+#[test]
+fn test_withdraw_listing_fees() {
+    let mut deps = setup();
+    for token_id in ["1", "2"] {
+        set_ask(
+            deps.as_mut(),
+            at(0),
+            ask_info(SaleType::FixedPrice, token_id, 100, 100),
+        );
+    }
+    assert_eq!(listing_fees(&deps), 20);
+
+    let withdraw = |amount: Option<u128>, weights: &[u64]| SudoMsg::WithdrawListingFees {
+        denom: DENOM.to_string(),
+        amount: amount.map(Uint128::new),
+        recipients: weights
+            .iter()
+            .enumerate()
+            .map(|(i, weight)| ListingFeeShare {
+                recipient: format!("recipient{}", i),
+                weight: *weight,
+            })
+            .collect(),
+    };
+    let err = sudo(deps.as_mut(), mock_env(), withdraw(None, &[0, 0])).unwrap_err();
+    assert_eq!(err, ContractError::InvalidListingFeeShares {});
+    let err = sudo(deps.as_mut(), mock_env(), withdraw(Some(21), &[1])).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InsufficientListingFees(Uint128::new(20))
+    );
+
+    // The rounding dust goes to the first recipient
+    let res = sudo(deps.as_mut(), mock_env(), withdraw(Some(11), &[1, 1, 1])).unwrap();
+    assert_eq!(
+        bank_sends(&res),
+        vec![
+            ("recipient0".to_string(), 5),
+            ("recipient1".to_string(), 3),
+            ("recipient2".to_string(), 3)
+        ]
+    );
+    assert_eq!(listing_fees(&deps), 9);
+
+    let res = sudo(deps.as_mut(), mock_env(), withdraw(None, &[1])).unwrap();
+    assert_eq!(bank_sends(&res), vec![("recipient0".to_string(), 9)]);
+    assert_eq!(listing_fees(&deps), 0);
+}
+
+#[test]
+fn test_refund_listing_fee_on_sale() {
+    let mut deps = setup();
+    set_ask(
+        deps.as_mut(),
+        at(0),
+        ask_info(SaleType::FixedPrice, "1", 100, 100),
+    );
+    SUDO_PARAMS
+        .update(&mut deps.storage, |mut params| -> StdResult<_> {
+            params.refund_listing_fee_on_sale = true;
+            Ok(params)
+        })
+        .unwrap();
+    set_ask(
+        deps.as_mut(),
+        at(0),
+        ask_info(SaleType::FixedPrice, "2", 100, 100),
+    );
+
+    // The listing fee paid for the ask is refunded to the seller along with the payout
+    for token_id in ["1", "2"] {
+        let res = set_bid(deps.as_mut(), at(10), "buyer", token_id, 100, None);
+        assert_eq!(
+            bank_sends(&res),
+            vec![
+                (SELLER.to_string(), 10),
+                (FEE_RECIPIENT.to_string(), 2),
+                (SELLER.to_string(), 98)
+            ]
+        );
+    }
+    assert_eq!(listing_fees(&deps), 0);
+}
+
+#[test]
+fn test_collection_registry() {
+    let mut deps = setup();
+
+    // Only the collection escrowing the NFT can list it
+    let rcv_msg = Cw721ReceiveMsg {
+        sender: SELLER.to_string(),
+        token_id: "1".to_string(),
+        msg: to_binary(&ReceiveNftMsg::SetAsk(ask_info(
+            SaleType::FixedPrice,
+            "1",
+            100,
+            100,
+        )))
+        .unwrap(),
+    };
+    let info = mock_info("other_collection", &coins(10, DENOM));
+    let err = execute(deps.as_mut(), at(0), info, ExecuteMsg::ReceiveNft(rcv_msg)).unwrap_err();
+    assert_eq!(err, ContractError::CollectionMismatch {});
+
+    let registered = |deps: &OwnedDeps<MockStorage, MockApi, MockQuerier>| {
+        let msg = QueryMsg::RegisteredCollection {
+            collection: COLLECTION.to_string(),
+        };
+        let res: RegisteredCollectionResponse =
+            from_binary(&query(deps.as_ref(), at(0), msg).unwrap()).unwrap();
+        res.collection
+            .map(|collection| (collection.first_listed_at, collection.active_asks))
+    };
+    assert_eq!(registered(&deps), None);
+
+    set_ask(
+        deps.as_mut(),
+        at(10),
+        ask_info(SaleType::FixedPrice, "1", 100, 100),
+    );
+    set_ask(
+        deps.as_mut(),
+        at(20),
+        ask_info(SaleType::FixedPrice, "2", 100, 100),
+    );
+    let first_listed_at = at(10).block.time;
+    assert_eq!(registered(&deps), Some((first_listed_at, 2)));
+
+    set_bid(deps.as_mut(), at(30), "buyer", "1", 100, None);
+    assert_eq!(registered(&deps), Some((first_listed_at, 1)));
+}
+
+#[test]
+fn test_collection_list_modes() {
+    let mut deps = setup();
+    let set_mode = |deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>, mode| {
+        SUDO_PARAMS
+            .update(&mut deps.storage, |mut params| -> StdResult<_> {
+                params.collection_list_mode = mode;
+                Ok(params)
+            })
+            .unwrap();
+    };
+    let list = |deps: DepsMut, token_id: &str| {
+        let rcv_msg = Cw721ReceiveMsg {
+            sender: SELLER.to_string(),
+            token_id: token_id.to_string(),
+            msg: to_binary(&ReceiveNftMsg::SetAsk(ask_info(
+                SaleType::FixedPrice,
+                token_id,
+                100,
+                100,
+            )))
+            .unwrap(),
+        };
+        let info = mock_info(COLLECTION, &coins(10, DENOM));
+        execute(deps, at(0), info, ExecuteMsg::ReceiveNft(rcv_msg))
+    };
+    let not_allowed = ContractError::CollectionNotAllowed(COLLECTION.to_string());
+
+    // Only allowlisted collections can be listed in allowlist mode
+    set_mode(&mut deps, CollectionListMode::Allowlist);
+    assert_eq!(list(deps.as_mut(), "1").unwrap_err(), not_allowed);
+    let collection = COLLECTION.to_string();
+    sudo(
+        deps.as_mut(),
+        mock_env(),
+        SudoMsg::AddAllowedCollection {
+            collection: collection.clone(),
+        },
+    )
+    .unwrap();
+    list(deps.as_mut(), "1").unwrap();
+
+    // Denylisted collections can't be listed or bid on in denylist mode
+    set_mode(&mut deps, CollectionListMode::Denylist);
+    sudo(
+        deps.as_mut(),
+        mock_env(),
+        SudoMsg::AddDeniedCollection {
+            collection: collection.clone(),
+        },
+    )
+    .unwrap();
+    assert_eq!(list(deps.as_mut(), "2").unwrap_err(), not_allowed);
+    let msg = ExecuteMsg::SetBid {
+        collection: collection.clone(),
+        token_id: "1".to_string(),
+        expires: None,
+        finder: None,
+    };
+    let err = execute(
+        deps.as_mut(),
+        at(10),
+        mock_info("buyer", &coins(100, DENOM)),
+        msg,
+    )
+    .unwrap_err();
+    assert_eq!(err, not_allowed);
+
+    let err = sudo(
+        deps.as_mut(),
+        mock_env(),
+        SudoMsg::RemoveAllowedCollection {
+            collection: "unknown".to_string(),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::CollectionNotFound("unknown".to_string())
+    );
+
+    // Verified collections are marked in the registry
+    sudo(
+        deps.as_mut(),
+        mock_env(),
+        SudoMsg::VerifyCollection {
+            collection: collection.clone(),
+        },
+    )
+    .unwrap();
+    let msg = QueryMsg::RegisteredCollection { collection };
+    let res: RegisteredCollectionResponse =
+        from_binary(&query(deps.as_ref(), at(0), msg).unwrap()).unwrap();
+    assert!(res.collection.unwrap().verified);
+}
+
+#[test]
+fn test_collection_params() {
+    let mut deps = setup();
+    let set_collection_params = |denom: &str| SudoMsg::SetCollectionParams {
+        collection: COLLECTION.to_string(),
+        min_prices: Some(vec![DenomMinPrice {
+            denom: Denom::Native(denom.to_string()),
+            min_price: Uint128::new(50),
+        }]),
+        listing_fee: None,
+        ask_expiry: None,
+        bid_expiry: None,
+        sale_types: Some(vec![SaleType::FixedPrice]),
+        trading_fee_bps: Some(500),
+    };
+    let err = sudo(deps.as_mut(), mock_env(), set_collection_params("uatom")).unwrap_err();
+    assert_eq!(err, ContractError::InvalidDenom("uatom".to_string()));
+    sudo(deps.as_mut(), mock_env(), set_collection_params(DENOM)).unwrap();
+
+    let msg = QueryMsg::CollectionParams {
+        collection: COLLECTION.to_string(),
+    };
+    let res: CollectionParamsResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+    assert_eq!(res.trading_fee_percent, Decimal::percent(500));
+    assert_eq!(res.listing_fee, coin(10, DENOM));
+    assert_eq!(res.sale_types, vec![SaleType::FixedPrice]);
+
+    let list = |deps: DepsMut, ask_info: AskInfo| {
+        let rcv_msg = Cw721ReceiveMsg {
+            sender: SELLER.to_string(),
+            token_id: ask_info.token_id.clone(),
+            msg: to_binary(&ReceiveNftMsg::SetAsk(ask_info)).unwrap(),
+        };
+        let info = mock_info(COLLECTION, &coins(10, DENOM));
+        execute(deps, at(0), info, ExecuteMsg::ReceiveNft(rcv_msg))
+    };
+    let err = list(deps.as_mut(), ask_info(SaleType::FixedPrice, "1", 40, 100)).unwrap_err();
+    assert_eq!(err, ContractError::PriceTooSmall(Uint128::new(40)));
+    let err = list(deps.as_mut(), ask_info(SaleType::Auction, "1", 100, 100)).unwrap_err();
+    assert_eq!(err, ContractError::SaleTypeNotAllowed {});
+
+    // Sales in the collection pay its trading fee
+    list(deps.as_mut(), ask_info(SaleType::FixedPrice, "1", 100, 100)).unwrap();
+    let res = set_bid(deps.as_mut(), at(10), "buyer", "1", 100, None);
+    assert_eq!(
+        bank_sends(&res),
+        vec![(FEE_RECIPIENT.to_string(), 5), (SELLER.to_string(), 95)]
+    );
+
+    sudo(
+        deps.as_mut(),
+        mock_env(),
+        SudoMsg::RemoveCollectionParams {
+            collection: COLLECTION.to_string(),
+        },
+    )
+    .unwrap();
+    list(deps.as_mut(), ask_info(SaleType::Auction, "2", 40, 100)).unwrap();
+}
+
+#[test]
+fn test_royalty_registry() {
+    let mut deps = mock_dependencies();
+    let msg = InstantiateMsg {
+        max_royalty_bps: 10001,
+        ..instantiate_msg()
+    };
+    let err = instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap_err();
+    assert_eq!(err, ContractError::InvalidMaxRoyaltyBps(10001));
+
+    let mut deps = setup();
+    let set_royalty = |royalty_rate| SudoMsg::SetRoyalty {
+        collection: COLLECTION.to_string(),
+        recipient: "creator".to_string(),
+        royalty_rate,
+        shares: None,
+    };
+    let err = sudo(deps.as_mut(), mock_env(), set_royalty(Decimal::percent(30))).unwrap_err();
+    assert_eq!(err, ContractError::InvalidRoyaltyRate(Decimal::percent(30)));
+    sudo(deps.as_mut(), mock_env(), set_royalty(Decimal::percent(10))).unwrap();
+
+    // A collection that doesn't report a royalty pays the registered one
+    set_ask(
+        deps.as_mut(),
+        at(0),
+        ask_info(SaleType::FixedPrice, "1", 1000, 100),
+    );
+    let res = set_bid(deps.as_mut(), at(10), "buyer", "1", 1000, None);
+    assert_eq!(
+        bank_sends(&res),
+        vec![
+            (FEE_RECIPIENT.to_string(), 20),
+            ("creator".to_string(), 98),
+            (SELLER.to_string(), 882),
+        ]
+    );
+
+    // A royalty reported by the collection is capped by the max royalty rate
+    let royalty = Royalty {
+        address: "minter".to_string(),
+        royalty_rate: Decimal::percent(50),
+        shares: vec![],
+    };
+    mock_collection(&mut deps, Some(royalty));
+    set_ask(
+        deps.as_mut(),
+        at(0),
+        ask_info(SaleType::FixedPrice, "2", 1000, 100),
+    );
+    let res = set_bid(deps.as_mut(), at(10), "buyer", "2", 1000, None);
+    assert_eq!(
+        bank_sends(&res),
+        vec![
+            (FEE_RECIPIENT.to_string(), 20),
+            ("minter".to_string(), 196),
+            (SELLER.to_string(), 784),
+        ]
+    );
+}
+
+#[test]
+fn test_royalty_split_dust() {
+    let mut deps = setup();
+    let shares = ["creator", "artist", "curator"]
+        .iter()
+        .map(|address| RoyaltyShare {
+            address: address.to_string(),
+            weight: 1,
+        })
+        .collect();
+    let msg = SudoMsg::SetRoyalty {
+        collection: COLLECTION.to_string(),
+        recipient: "creator".to_string(),
+        royalty_rate: Decimal::percent(10),
+        shares: Some(shares),
+    };
+    sudo(deps.as_mut(), mock_env(), msg).unwrap();
+
+    set_ask(
+        deps.as_mut(),
+        at(0),
+        ask_info(SaleType::FixedPrice, "1", 1000, 100),
+    );
+    let res = set_bid(deps.as_mut(), at(10), "buyer", "1", 1000, None);
+
+    // The royalty of 98 is split in thirds of 32, and the dust goes to the first recipient
+    assert_eq!(
+        bank_sends(&res),
+        vec![
+            (FEE_RECIPIENT.to_string(), 20),
+            ("creator".to_string(), 34),
+            ("artist".to_string(), 32),
+            ("curator".to_string(), 32),
+            (SELLER.to_string(), 882),
+        ]
+    );
+    assert_eq!(nft_transfers(&res), vec!["buyer".to_string()]);
+}
+
+#[test]
+fn test_royalty_split_by_weight() {
+    let mut deps = setup();
+    let royalty = |weights: [u64; 2]| Royalty {
+        address: "minter".to_string(),
+        royalty_rate: Decimal::percent(10),
+        shares: vec![
+            RoyaltyShare {
+                address: "creator".to_string(),
+                weight: weights[0],
+            },
+            RoyaltyShare {
+                address: "artist".to_string(),
+                weight: weights[1],
+            },
+        ],
+    };
+    mock_collection(&mut deps, Some(royalty([3, 1])));
+    set_ask(
+        deps.as_mut(),
+        at(0),
+        ask_info(SaleType::FixedPrice, "1", 1000, 100),
+    );
+    let res = set_bid(deps.as_mut(), at(10), "buyer", "1", 1000, None);
+    assert_eq!(
+        bank_sends(&res),
+        vec![
+            (FEE_RECIPIENT.to_string(), 20),
+            ("creator".to_string(), 74),
+            ("artist".to_string(), 24),
+            (SELLER.to_string(), 882),
+        ]
+    );
+
+    // Weights that don't fit in a u64 when added up are still split evenly
+    mock_collection(&mut deps, Some(royalty([u64::MAX, u64::MAX])));
+    set_ask(
+        deps.as_mut(),
+        at(0),
+        ask_info(SaleType::FixedPrice, "2", 1000, 100),
+    );
+    let res = set_bid(deps.as_mut(), at(10), "buyer", "2", 1000, None);
+    assert_eq!(
+        bank_sends(&res),
+        vec![
+            (FEE_RECIPIENT.to_string(), 20),
+            ("creator".to_string(), 49),
+            ("artist".to_string(), 49),
+            (SELLER.to_string(), 882),
+        ]
+    );
+}
+
+#[test]
+fn test_royalty_shares_need_weight() {
+    let mut deps = setup();
+    let msg = SudoMsg::SetRoyalty {
+        collection: COLLECTION.to_string(),
+        recipient: "creator".to_string(),
+        royalty_rate: Decimal::percent(10),
+        shares: Some(vec![RoyaltyShare {
+            address: "creator".to_string(),
+            weight: 0,
+        }]),
+    };
+    let err = sudo(deps.as_mut(), mock_env(), msg).unwrap_err();
+    assert_eq!(err, ContractError::InvalidRoyaltyShares {});
+}
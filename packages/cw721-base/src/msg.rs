@@ -2,7 +2,7 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use crate::{ContractError};
-use cosmwasm_std::{Binary, Decimal, Uint128, Coin};
+use cosmwasm_std::{Api, Binary, Decimal, Uint128, Coin};
 use cw721::Expiration;
 
 
@@ -215,15 +215,55 @@ pub struct AdminResponse {
 pub struct Royalty {
     pub address: String,
     pub royalty_rate: Decimal,
+    /// Splits the royalty between several recipients instead of paying it to `address`
+    #[serde(default)]
+    pub shares: Vec<RoyaltyShare>,
+}
+
+/// Share of a royalty paid to a recipient, weighted against the other shares
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RoyaltyShare {
+    pub address: String,
+    pub weight: u64,
 }
 
 
 impl Royalty {
-    pub fn royalty_rate_validate(&self) -> Result<Decimal, ContractError> {
+    pub fn royalty_rate_validate(&self, api: &dyn Api) -> Result<Decimal, ContractError> {
         if self.royalty_rate > Decimal::one() {
             return Err(ContractError::InvalidRoyalities {});
         }
 
+        api.addr_validate(&self.address)?;
+        for share in &self.shares {
+            api.addr_validate(&share.address)?;
+            if share.weight == 0 {
+                return Err(ContractError::InvalidRoyalities {});
+            }
+        }
+
         Ok(self.royalty_rate)
     }
+
+    /// Splits a royalty amount between the recipients by weight. Each share is rounded
+    /// down, and the rounding dust is paid to the first recipient.
+    pub fn split(&self, amount: Uint128) -> Vec<(String, Uint128)> {
+        if self.shares.is_empty() {
+            return vec![(self.address.clone(), amount)];
+        }
+
+        let total_weight: u128 = self.shares.iter().map(|share| u128::from(share.weight)).sum();
+        let mut payouts: Vec<(String, Uint128)> = self
+            .shares
+            .iter()
+            .map(|share| {
+                let share_amount = amount.multiply_ratio(share.weight, total_weight);
+                (share.address.clone(), share_amount)
+            })
+            .collect();
+        let paid: Uint128 = payouts.iter().map(|(_, share_amount)| share_amount).sum();
+        payouts[0].1 += amount.saturating_sub(paid);
+
+        payouts
+    }
 }
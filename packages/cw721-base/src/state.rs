@@ -154,6 +154,16 @@ pub struct Metadata {
 pub struct Royalty {
     pub address: Addr,
     pub royalty_rate: Decimal,
+    /// Splits the royalty between several recipients instead of paying it to `address`
+    #[serde(default)]
+    pub shares: Vec<RoyaltyShare>,
+}
+
+/// Share of a royalty paid to a recipient, weighted against the other shares
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RoyaltyShare {
+    pub address: Addr,
+    pub weight: u64,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]